@@ -0,0 +1,7 @@
+pub mod pitch_detection;
+pub mod synthesis;
+pub mod transcription;
+
+pub use pitch_detection::*;
+pub use synthesis::*;
+pub use transcription::*;
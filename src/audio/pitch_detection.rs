@@ -0,0 +1,353 @@
+//! 単音のピッチ検出（YIN 法）
+//! マイクのフレーム（f32 の PCM）から基本周波数を求め、チューナー表示に使う
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::core::frequency::{cents_between, parse_reference, NearestPitch, ReferencePitch};
+use crate::error::{to_js, SidFretError};
+use crate::instrument::fingering::algorithm::parse_tuning;
+use crate::instrument::tuning::{OpenString, Tuning};
+
+/// 検出する最低周波数の既定値（5弦の B0 ≒ 30.87Hz より少し下）
+pub const DEFAULT_MIN_FREQUENCY: f64 = 28.0;
+/// 検出する最高周波数の既定値（1弦24フレットの G4 ≒ 392Hz と倍音の余裕）
+pub const DEFAULT_MAX_FREQUENCY: f64 = 1000.0;
+/// 累積平均正規化差分関数の閾値の既定値（小さいほど厳しい）
+pub const DEFAULT_THRESHOLD: f64 = 0.15;
+/// これより小さい RMS は無音として扱う
+pub(crate) const SILENCE_RMS: f64 = 1e-3;
+
+/// 検出した基本周波数
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PitchDetection {
+    /// 基本周波数（Hz）
+    pub frequency: f64,
+    /// 確からしさ（0〜1、周期性が強いほど 1 に近い）
+    pub confidence: f64,
+}
+
+/// YIN 法によるピッチ検出器
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PitchDetector {
+    sample_rate: f64,
+    min_frequency: f64,
+    max_frequency: f64,
+    threshold: f64,
+}
+
+impl PitchDetector {
+    /// サンプリング周波数を指定して生成（正の有限値でない場合は None）
+    pub fn new(sample_rate: f64) -> Option<Self> {
+        (sample_rate.is_finite() && sample_rate > 0.0).then_some(Self {
+            sample_rate,
+            min_frequency: DEFAULT_MIN_FREQUENCY,
+            max_frequency: DEFAULT_MAX_FREQUENCY,
+            threshold: DEFAULT_THRESHOLD,
+        })
+    }
+
+    /// 検出する周波数の範囲を変更（0 < 最低 < 最高 の有限値でない場合は None）
+    pub fn with_frequency_range(mut self, min_frequency: f64, max_frequency: f64) -> Option<Self> {
+        let valid = min_frequency.is_finite()
+            && max_frequency.is_finite()
+            && 0.0 < min_frequency
+            && min_frequency < max_frequency;
+        valid.then(|| {
+            self.min_frequency = min_frequency;
+            self.max_frequency = max_frequency;
+            self
+        })
+    }
+
+    /// サンプリング周波数（Hz）
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// 検出する最低周波数（Hz）
+    pub fn min_frequency(&self) -> f64 {
+        self.min_frequency
+    }
+
+    /// 周期とみなす閾値を変更（0〜1 の範囲外は None）
+    pub fn with_threshold(mut self, threshold: f64) -> Option<Self> {
+        (0.0 < threshold && threshold < 1.0).then(|| {
+            self.threshold = threshold;
+            self
+        })
+    }
+
+    /// 最低周波数まで検出するのに必要なサンプル数（44.1kHz で約 3150、4096 を推奨）
+    pub fn min_samples(&self) -> usize {
+        self.max_lag().saturating_mul(2)
+    }
+
+    fn max_lag(&self) -> usize {
+        (self.sample_rate / self.min_frequency).ceil() as usize
+    }
+
+    fn min_lag(&self) -> usize {
+        ((self.sample_rate / self.max_frequency).floor() as usize).max(2)
+    }
+
+    /// 基本周波数を検出（無音・周期性がない場合は None）
+    /// サンプル数が min_samples より少ない場合は、その長さで測れる周波数までを探す
+    pub fn detect(&self, samples: &[f32]) -> Option<PitchDetection> {
+        let min_lag = self.min_lag();
+        let max_lag = self.max_lag().min(samples.len() / 2);
+        if max_lag <= min_lag.saturating_add(1) || rms(samples) < SILENCE_RMS {
+            return None;
+        }
+
+        let cmnd = cumulative_mean_normalized_difference(samples, max_lag);
+
+        // 閾値を下回る最初の谷（倍音の周期より基本周期を優先する）
+        let mut lag = (min_lag..max_lag).find(|&lag| cmnd[lag] < self.threshold)?;
+        while lag + 1 < max_lag && cmnd[lag + 1] < cmnd[lag] {
+            lag += 1;
+        }
+
+        Some(PitchDetection {
+            frequency: self.sample_rate / parabolic_peak(&cmnd, lag),
+            confidence: (1.0 - cmnd[lag]).clamp(0.0, 1.0),
+        })
+    }
+}
+
+/// 累積平均正規化差分関数 d'(τ)（YIN の手順 2・3）
+fn cumulative_mean_normalized_difference(samples: &[f32], max_lag: usize) -> Vec<f64> {
+    let window = samples.len() - max_lag;
+    let mut cmnd = vec![1.0; max_lag + 1];
+    let mut running_sum = 0.0;
+    for lag in 1..=max_lag {
+        let difference: f64 = samples[..window]
+            .iter()
+            .zip(&samples[lag..lag + window])
+            .map(|(&a, &b)| {
+                let d = a as f64 - b as f64;
+                d * d
+            })
+            .sum();
+        running_sum += difference;
+        if running_sum > 0.0 {
+            cmnd[lag] = difference * lag as f64 / running_sum;
+        }
+    }
+    cmnd
+}
+
+/// 谷の前後3点を放物線で補間した周期（サンプル単位の小数）
+fn parabolic_peak(values: &[f64], index: usize) -> f64 {
+    if index == 0 || index + 1 >= values.len() {
+        return index as f64;
+    }
+    let (left, center, right) = (values[index - 1], values[index], values[index + 1]);
+    let denominator = left - 2.0 * center + right;
+    if denominator.abs() < f64::EPSILON {
+        index as f64
+    } else {
+        index as f64 + 0.5 * (left - right) / denominator
+    }
+}
+
+pub(crate) fn rms(samples: &[f32]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = samples.iter().map(|&s| s as f64 * s as f64).sum();
+    (sum / samples.len() as f64).sqrt()
+}
+
+/// チューナーの表示内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TunerReading {
+    pub frequency: f64,
+    pub confidence: f64,
+    /// 最も近い音とセント差
+    pub nearest: NearestPitch,
+    /// 最も近い開放弦
+    pub string: OpenString,
+    /// 開放弦からのずれ（セント、正は高い）
+    pub string_cents: f64,
+}
+
+impl TunerReading {
+    /// 検出結果に最も近い音と開放弦を求める（弦のないチューニングは None）
+    pub fn new(
+        detection: PitchDetection,
+        tuning: &Tuning,
+        reference: ReferencePitch,
+    ) -> Option<Self> {
+        let nearest = reference.nearest_pitch(detection.frequency)?;
+        let (string, string_cents) = tuning
+            .open_strings(reference)
+            .into_iter()
+            .map(|open| {
+                let cents = cents_between(open.frequency, detection.frequency);
+                (open, cents)
+            })
+            .min_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))?;
+        Some(Self {
+            frequency: detection.frequency,
+            confidence: detection.confidence,
+            nearest,
+            string,
+            string_cents,
+        })
+    }
+}
+
+/// PCM フレームからチューナーの表示内容を求める（音が検出できない場合は None）
+pub fn tune(
+    samples: &[f32],
+    detector: &PitchDetector,
+    tuning: &Tuning,
+    reference: ReferencePitch,
+) -> Option<TunerReading> {
+    TunerReading::new(detector.detect(samples)?, tuning, reference)
+}
+
+/// WASM公開API: マイクのフレームからチューナーの表示内容を求める
+/// `TunerReading` を返す（音が検出できない場合は null、不正な引数は例外）
+#[wasm_bindgen]
+pub fn detect_pitch(
+    samples: &[f32],
+    sample_rate: f64,
+    tuning_name: &str,
+    a4: f64,
+) -> Result<JsValue, JsValue> {
+    let detector = PitchDetector::new(sample_rate)
+        .ok_or_else(|| SidFretError::InvalidInput(format!("invalid sample rate {sample_rate}")))?;
+    let tuning = parse_tuning(tuning_name)?;
+    let reference = parse_reference(a4)?;
+    match tune(samples, &detector, &tuning, reference) {
+        Some(reading) => Ok(to_js(&reading)?),
+        None => Ok(JsValue::NULL),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::pitch::Pitch;
+    use std::f64::consts::TAU;
+
+    const SAMPLE_RATE: f64 = 44100.0;
+    const FRAME: usize = 4096;
+
+    /// 倍音を含む正弦波（harmonics は各倍音の振幅）
+    fn tone(frequency: f64, harmonics: &[f64], len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let t = i as f64 / SAMPLE_RATE;
+                harmonics
+                    .iter()
+                    .enumerate()
+                    .map(|(h, amp)| amp * (TAU * frequency * (h + 1) as f64 * t).sin())
+                    .sum::<f64>() as f32
+                    * 0.5
+            })
+            .collect()
+    }
+
+    fn detect(samples: &[f32]) -> Option<PitchDetection> {
+        PitchDetector::new(SAMPLE_RATE).unwrap().detect(samples)
+    }
+
+    #[test]
+    fn test_detect_sine_in_bass_range() {
+        for frequency in [30.87, 41.2, 55.0, 73.42, 98.0, 196.0, 392.0] {
+            let detection = detect(&tone(frequency, &[1.0], FRAME)).unwrap();
+            assert!(
+                cents_between(frequency, detection.frequency).abs() < 2.0,
+                "{frequency} → {}",
+                detection.frequency
+            );
+            assert!(detection.confidence > 0.9);
+        }
+    }
+
+    #[test]
+    fn test_detect_fundamental_with_strong_harmonics() {
+        // 倍音の方が強いベースらしい音色でもオクターブ上と誤検出しない
+        let samples = tone(41.2, &[0.4, 1.0, 0.7, 0.5, 0.3], FRAME);
+        let detection = detect(&samples).unwrap();
+        assert!(cents_between(41.2, detection.frequency).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_detect_rejects_silence_and_noise() {
+        assert_eq!(detect(&vec![0.0; FRAME]), None);
+        assert_eq!(detect(&[]), None);
+
+        // 線形合同法の疑似乱数によるノイズ
+        let mut state = 12345u32;
+        let noise: Vec<f32> = (0..FRAME)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as f32 / 32768.0 - 1.0
+            })
+            .collect();
+        assert_eq!(detect(&noise), None);
+    }
+
+    #[test]
+    fn test_short_frame_limits_lowest_frequency() {
+        let detector = PitchDetector::new(SAMPLE_RATE).unwrap();
+        assert!(detector.min_samples() <= FRAME);
+        // 短いフレームでは B0 の周期が2つ入らない
+        assert_eq!(detector.detect(&tone(30.87, &[1.0], 1024)), None);
+        assert!(detector.detect(&tone(98.0, &[1.0], 1024)).is_some());
+    }
+
+    #[test]
+    fn test_invalid_range_and_threshold() {
+        let detector = PitchDetector::new(SAMPLE_RATE).unwrap();
+        assert!(detector.with_frequency_range(0.0, 0.0).is_none());
+        assert!(detector.with_frequency_range(30.0, 0.0).is_none());
+        assert!(detector.with_frequency_range(400.0, 30.0).is_none());
+        assert!(detector.with_frequency_range(-30.0, 400.0).is_none());
+        assert!(detector.with_frequency_range(30.0, f64::INFINITY).is_none());
+        assert!(detector.with_frequency_range(f64::NAN, 400.0).is_none());
+        assert!(detector.with_threshold(0.0).is_none());
+        assert!(detector.with_threshold(1.0).is_none());
+        assert!(detector.with_threshold(f64::NAN).is_none());
+
+        let narrowed = detector.with_frequency_range(80.0, 400.0).unwrap();
+        assert_eq!(narrowed.min_frequency(), 80.0);
+        assert!(narrowed.detect(&tone(98.0, &[1.0], FRAME)).is_some());
+        assert!(narrowed.with_threshold(0.2).is_some());
+    }
+
+    #[test]
+    fn test_tuner_reading() {
+        let tuning = Tuning::bass_5();
+        let reference = ReferencePitch::STANDARD;
+        let detector = PitchDetector::new(SAMPLE_RATE).unwrap();
+
+        // 15セント低い A弦
+        let flat_a = 55.0 * (-15.0f64 / 1200.0).exp2();
+        let reading = tune(
+            &tone(flat_a, &[1.0, 0.5], FRAME),
+            &detector,
+            &tuning,
+            reference,
+        )
+        .unwrap();
+        assert_eq!(reading.nearest.pitch, "A1".parse::<Pitch>().unwrap());
+        assert!((reading.nearest.cents + 15.0).abs() < 2.0);
+        assert_eq!(reading.string.string, 3);
+        assert!((reading.string_cents + 15.0).abs() < 2.0);
+
+        // 5弦の B0
+        let reading = tune(&tone(30.87, &[1.0], FRAME), &detector, &tuning, reference).unwrap();
+        assert_eq!(reading.string.string, 5);
+        assert_eq!(reading.nearest.pitch.to_string(), "B0");
+
+        // 基準ピッチ 442Hz では 440Hz 基準の A1 は低い
+        let a442 = ReferencePitch::new(442.0).unwrap();
+        let reading = tune(&tone(55.0, &[1.0], FRAME), &detector, &tuning, a442).unwrap();
+        assert!((reading.nearest.cents - cents_between(55.25, 55.0)).abs() < 2.0);
+    }
+}
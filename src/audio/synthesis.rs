@@ -0,0 +1,522 @@
+//! ベース音の合成（Karplus-Strong 法）と WAV 書き出し
+//! 音列や運指をプレビュー用の PCM に変換する
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::core::frequency::{parse_reference, ReferencePitch};
+use crate::error::{from_js, SidFretError};
+use crate::instrument::fingering::algorithm::parse_tuning;
+use crate::instrument::fingering::input::pitch_to_midi;
+use crate::instrument::fingering::position::FingeringPattern;
+use crate::instrument::fingering::rhythm::{beats_to_seconds, parse_bpm, NoteEvent};
+use crate::instrument::tuning::Tuning;
+
+/// 音の終わりで弦をミュートしてから消えるまで（秒）
+const RELEASE_SECONDS: f64 = 0.05;
+/// 全体の音量（複数の音が重なっても割れにくいよう控えめにする）
+const MASTER_GAIN: f64 = 0.5;
+/// 合成できる長さの上限（サンプル数、48kHz で10分）
+const MAX_RENDER_SAMPLES: f64 = 48_000.0 * 600.0;
+/// 遅延線の1周期の下限（サンプル数、これより短いとオールパスで調律できない）
+const MIN_PERIOD_SAMPLES: f64 = 2.6;
+
+/// 弦ごとの音色
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StringTimbre {
+    /// 弾いた瞬間の明るさ（0〜1、大きいほど高域が多い）
+    pub brightness: f64,
+    /// 音量が 60dB 下がるまでの時間（秒）
+    pub sustain: f64,
+    /// 音量（0〜1）
+    pub gain: f64,
+}
+
+impl StringTimbre {
+    /// 弦番号（1=最高音弦）に応じた音色（太い弦ほど暗く、長く伸びる）
+    pub fn for_string(string: u8, string_count: usize) -> Self {
+        // 最低音弦 = 0.0、最高音弦 = 1.0
+        let height = if string_count > 1 {
+            (string_count - string.clamp(1, string_count as u8) as usize) as f64
+                / (string_count - 1) as f64
+        } else {
+            0.5
+        };
+        Self {
+            brightness: 0.35 + 0.35 * height,
+            sustain: 3.5 - 1.5 * height,
+            gain: 0.8,
+        }
+    }
+}
+
+impl Default for StringTimbre {
+    fn default() -> Self {
+        Self {
+            brightness: 0.5,
+            sustain: 2.75,
+            gain: 0.8,
+        }
+    }
+}
+
+/// 合成する音（時間の単位は秒）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SynthNote {
+    pub frequency: f64,
+    pub onset: f64,
+    pub duration: f64,
+    pub timbre: StringTimbre,
+}
+
+/// Karplus-Strong 法のベース音源
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BassSynth {
+    sample_rate: u32,
+    reference: ReferencePitch,
+}
+
+impl BassSynth {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            reference: ReferencePitch::STANDARD,
+        }
+    }
+
+    pub fn with_reference(mut self, reference: ReferencePitch) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// 音を重ねて PCM（-1.0〜1.0 のモノラル）に合成（負の発音位置と長さは 0 とみなす）
+    /// 時間が有限でない音、サンプリング周波数に対して高すぎる音、長すぎる合成はエラー
+    pub fn render(&self, notes: &[SynthNote]) -> Result<Vec<f32>, SidFretError> {
+        let sr = self.sample_rate as f64;
+        for (index, note) in notes.iter().enumerate() {
+            if !note.onset.is_finite() || !note.duration.is_finite() {
+                return Err(SidFretError::InvalidInput(format!(
+                    "invalid timing for note {index}"
+                )));
+            }
+            if note.frequency * MIN_PERIOD_SAMPLES > sr {
+                return Err(SidFretError::InvalidInput(format!(
+                    "frequency {} Hz is too high for sample rate {}",
+                    note.frequency, self.sample_rate
+                )));
+            }
+        }
+        let total = notes
+            .iter()
+            .map(|n| n.onset.max(0.0) + n.duration.max(0.0) + RELEASE_SECONDS)
+            .fold(0.0, f64::max);
+        let len = (total * sr).ceil();
+        if len > MAX_RENDER_SAMPLES {
+            return Err(SidFretError::InvalidInput(format!(
+                "{len} samples exceed the limit of {MAX_RENDER_SAMPLES}"
+            )));
+        }
+        let mut buffer = vec![0.0f64; len as usize];
+
+        for (i, note) in notes.iter().enumerate() {
+            let start = (note.onset.max(0.0) * sr).round() as usize;
+            let Some(rest) = buffer.get_mut(start..) else {
+                continue;
+            };
+            let voice = pluck(note, sr, i as u32);
+            for (out, sample) in rest.iter_mut().zip(voice) {
+                *out += sample;
+            }
+        }
+        Ok(buffer
+            .into_iter()
+            .map(|s| (s * MASTER_GAIN).clamp(-1.0, 1.0) as f32)
+            .collect())
+    }
+
+    /// 音列（拍単位、E1=0基準）を合成（不正なテンポ・render で合成できない音はエラー）
+    pub fn render_events(&self, events: &[NoteEvent], bpm: f32) -> Result<Vec<f32>, SidFretError> {
+        let bpm = parse_bpm(bpm)?;
+        let notes: Vec<SynthNote> = events
+            .iter()
+            .map(|event| SynthNote {
+                frequency: self
+                    .reference
+                    .midi_to_frequency(pitch_to_midi(event.pitch) as f64),
+                onset: beats_to_seconds(event.onset, bpm) as f64,
+                duration: beats_to_seconds(event.duration, bpm) as f64,
+                timbre: StringTimbre::default(),
+            })
+            .collect();
+        self.render(&notes)
+    }
+
+    /// 運指を弦ごとの音色で合成
+    /// timing は各音の発音タイミング（ピッチは運指から求める、None なら1音1拍）
+    pub fn render_pattern(
+        &self,
+        pattern: &FingeringPattern,
+        tuning: &Tuning,
+        timing: Option<&[NoteEvent]>,
+        bpm: f32,
+    ) -> Result<Vec<f32>, SidFretError> {
+        let bpm = parse_bpm(bpm)?;
+        let uniform;
+        let timing = match timing {
+            Some(events) => events,
+            None => {
+                let beats: Vec<(i32, f32)> = pattern.positions.iter().map(|_| (0, 1.0)).collect();
+                uniform = NoteEvent::sequence(&beats);
+                &uniform
+            }
+        };
+        if timing.len() != pattern.positions.len() {
+            return Err(SidFretError::InvalidInput(format!(
+                "{} timing events for {} positions",
+                timing.len(),
+                pattern.positions.len()
+            )));
+        }
+
+        let notes: Vec<SynthNote> = pattern
+            .positions
+            .iter()
+            .zip(timing)
+            .map(|(position, event)| SynthNote {
+                frequency: self
+                    .reference
+                    .midi_to_frequency(pitch_to_midi(position.absolute_pitch(tuning)) as f64),
+                onset: beats_to_seconds(event.onset, bpm) as f64,
+                duration: beats_to_seconds(event.duration, bpm) as f64,
+                timbre: StringTimbre::for_string(position.string, tuning.strings.len()),
+            })
+            .collect();
+        self.render(&notes)
+    }
+}
+
+/// 1音分の Karplus-Strong 合成（ノイズで満たした遅延線をローパスで減衰させながら循環させる）
+fn pluck(note: &SynthNote, sample_rate: f64, seed: u32) -> Vec<f64> {
+    let sustain_len = (note.duration.max(0.0) * sample_rate) as usize;
+    let release_len = (RELEASE_SECONDS * sample_rate) as usize;
+    if note.frequency <= 0.0
+        || !note.frequency.is_finite()
+        || note.frequency * MIN_PERIOD_SAMPLES > sample_rate
+    {
+        return vec![];
+    }
+
+    // 遅延線 n + 平均フィルタ 0.5 + オールパス delta サンプルで1周期（delta は 0.1〜1.1）
+    let period = sample_rate / note.frequency;
+    let n = ((period - 0.6).floor() as usize).max(2);
+    let delta = period - 0.5 - n as f64;
+    let allpass = (1.0 - delta) / (1.0 + delta);
+    // 1周期ごとの減衰（sustain 秒で -60dB）
+    let loss = 10f64.powf(-3.0 / (note.frequency * note.timbre.sustain.max(0.01)));
+    // ミュート後は RELEASE_SECONDS で -60dB
+    let mute = 10f64.powf(-3.0 / (note.frequency * RELEASE_SECONDS));
+
+    let mut line = excitation(n, note.timbre.brightness, seed);
+    let (mut pos, mut last) = (0, 0.0);
+    let (mut ap_in, mut ap_out) = (0.0, 0.0);
+    (0..sustain_len + release_len)
+        .map(|i| {
+            let delayed = line[pos];
+            let averaged = 0.5 * (delayed + last);
+            last = delayed;
+            let shifted = allpass * averaged + ap_in - allpass * ap_out;
+            ap_in = averaged;
+            ap_out = shifted;
+            let gain = if i < sustain_len { loss } else { mute };
+            line[pos] = shifted * gain;
+            pos = (pos + 1) % n;
+            delayed * note.timbre.gain
+        })
+        .collect()
+}
+
+/// 弾いた瞬間の弦の形（明るさに応じてローパスをかけた直流成分のないノイズ）
+fn excitation(len: usize, brightness: f64, seed: u32) -> Vec<f64> {
+    let mut state = seed.wrapping_mul(2_654_435_761).wrapping_add(1);
+    let coefficient = brightness.clamp(0.05, 1.0);
+    let mut smoothed = 0.0;
+    let mut noise: Vec<f64> = (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let white = (state >> 8) as f64 / (1u32 << 24) as f64 * 2.0 - 1.0;
+            smoothed += coefficient * (white - smoothed);
+            smoothed
+        })
+        .collect();
+
+    let mean = noise.iter().sum::<f64>() / len as f64;
+    let peak = noise
+        .iter()
+        .map(|s| (s - mean).abs())
+        .fold(0.0, f64::max)
+        .max(f64::EPSILON);
+    for s in &mut noise {
+        *s = (*s - mean) / peak;
+    }
+    noise
+}
+
+/// PCM をモノラル 16bit の WAV バイト列に変換
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    const CHANNELS: u16 = 1;
+    const BITS_PER_SAMPLE: u16 = 16;
+    let block_align = CHANNELS * BITS_PER_SAMPLE / 8;
+    let data_len = samples.len() as u32 * block_align as u32;
+
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // リニア PCM
+    wav.extend_from_slice(&CHANNELS.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for &sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+        wav.extend_from_slice(&value.to_le_bytes());
+    }
+    wav
+}
+
+fn parse_sample_rate(sample_rate: u32) -> Result<BassSynth, SidFretError> {
+    if sample_rate == 0 {
+        return Err(SidFretError::InvalidInput(
+            "sample rate must be positive".to_string(),
+        ));
+    }
+    Ok(BassSynth::new(sample_rate))
+}
+
+/// WASM公開API: 音列（`NoteEvent[]`）を合成して WAV のバイト列を返す
+#[wasm_bindgen]
+pub fn render_events_wav(
+    events: JsValue,
+    bpm: f32,
+    a4: f64,
+    sample_rate: u32,
+) -> Result<Vec<u8>, JsValue> {
+    let synth = parse_sample_rate(sample_rate)?.with_reference(parse_reference(a4)?);
+    let events: Vec<NoteEvent> = from_js(events)?;
+    let samples = synth.render_events(&events, bpm)?;
+    Ok(encode_wav(&samples, sample_rate))
+}
+
+/// WASM公開API: 運指（`FingeringPattern`）を弦ごとの音色で合成して WAV のバイト列を返す
+/// events は発音タイミング（`NoteEvent[]`、null なら1音1拍）
+#[wasm_bindgen]
+pub fn render_fingering_wav(
+    pattern: JsValue,
+    tuning_name: &str,
+    events: JsValue,
+    bpm: f32,
+    a4: f64,
+    sample_rate: u32,
+) -> Result<Vec<u8>, JsValue> {
+    let synth = parse_sample_rate(sample_rate)?.with_reference(parse_reference(a4)?);
+    let tuning = parse_tuning(tuning_name)?;
+    let pattern: FingeringPattern = from_js(pattern)?;
+    let events: Option<Vec<NoteEvent>> = from_js(events)?;
+    let samples = synth.render_pattern(&pattern, &tuning, events.as_deref(), bpm)?;
+    Ok(encode_wav(&samples, sample_rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::pitch_detection::PitchDetector;
+    use crate::core::frequency::cents_between;
+    use crate::instrument::fingering::position::FretPosition;
+
+    const SAMPLE_RATE: u32 = 22050;
+
+    fn note(frequency: f64, onset: f64, duration: f64) -> SynthNote {
+        SynthNote {
+            frequency,
+            onset,
+            duration,
+            timbre: StringTimbre::default(),
+        }
+    }
+
+    fn detected_frequency(samples: &[f32]) -> f64 {
+        PitchDetector::new(SAMPLE_RATE as f64)
+            .unwrap()
+            .detect(samples)
+            .unwrap()
+            .frequency
+    }
+
+    /// 隣り合うサンプルの差のエネルギーの割合（高域が多いほど大きい）
+    fn roughness(samples: &[f32]) -> f64 {
+        let diff: f64 = samples
+            .windows(2)
+            .map(|w| ((w[1] - w[0]) as f64).powi(2))
+            .sum();
+        let energy: f64 = samples.iter().map(|&s| (s as f64).powi(2)).sum();
+        diff / energy
+    }
+
+    #[test]
+    fn test_pluck_is_in_tune() {
+        let synth = BassSynth::new(SAMPLE_RATE);
+        for frequency in [30.87, 41.2, 55.0, 98.0, 196.0, 392.0] {
+            let samples = synth.render(&[note(frequency, 0.0, 1.0)]).unwrap();
+            let frame = &samples[2205..2205 + 4096];
+            let detected = detected_frequency(frame);
+            assert!(
+                cents_between(frequency, detected).abs() < 3.0,
+                "{frequency} → {detected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_timing_and_release() {
+        let synth = BassSynth::new(SAMPLE_RATE);
+        let samples = synth
+            .render(&[note(55.0, 0.0, 0.5), note(82.4, 0.75, 0.25)])
+            .unwrap();
+        // 最後の音の終わり + ミュート
+        assert_eq!(samples.len(), (1.05 * SAMPLE_RATE as f64).ceil() as usize);
+        assert!(samples.iter().all(|s| s.abs() <= 1.0));
+
+        let peak = |from: f64, to: f64| {
+            let range = (from * SAMPLE_RATE as f64) as usize..(to * SAMPLE_RATE as f64) as usize;
+            samples[range].iter().fold(0.0f32, |m, s| m.max(s.abs()))
+        };
+        assert!(peak(0.0, 0.1) > 0.1);
+        // ミュート後の休符は無音
+        assert!(peak(0.6, 0.75) < 1e-3);
+        assert!(peak(0.75, 0.85) > 0.1);
+    }
+
+    #[test]
+    fn test_render_events_and_pattern() {
+        let synth = BassSynth::new(SAMPLE_RATE);
+        // 120BPM の A1（E1=0 基準で 5）を1拍
+        let samples = synth
+            .render_events(&[NoteEvent::new(5, 0.0, 1.0)], 120.0)
+            .unwrap();
+        assert_eq!(samples.len(), (0.55 * SAMPLE_RATE as f64).ceil() as usize);
+        assert!(cents_between(55.0, detected_frequency(&samples[..4096])).abs() < 3.0);
+
+        let tuning = Tuning::bass_4();
+        // 同じ A2 を E弦と G弦で弾くと G弦の方が明るい
+        let low = FingeringPattern::new(vec![FretPosition::new(4, 17)], "test".to_string());
+        let high = FingeringPattern::new(vec![FretPosition::new(1, 2)], "test".to_string());
+        let low = synth.render_pattern(&low, &tuning, None, 120.0).unwrap();
+        let high = synth.render_pattern(&high, &tuning, None, 120.0).unwrap();
+        assert!(roughness(&high[..4096]) > roughness(&low[..4096]));
+        assert!(cents_between(110.0, detected_frequency(&low[..4096])).abs() < 3.0);
+
+        let pattern = FingeringPattern::new(
+            vec![FretPosition::new(4, 0), FretPosition::new(3, 2)],
+            "test".to_string(),
+        );
+        let timing = NoteEvent::sequence(&[(0, 0.5), (0, 1.5)]);
+        let samples = synth
+            .render_pattern(&pattern, &tuning, Some(&timing), 120.0)
+            .unwrap();
+        assert_eq!(samples.len(), (1.05 * SAMPLE_RATE as f64).ceil() as usize);
+        assert_eq!(
+            synth
+                .render_pattern(&pattern, &tuning, Some(&timing[..1]), 120.0)
+                .unwrap_err()
+                .code(),
+            "INVALID_INPUT"
+        );
+    }
+
+    #[test]
+    fn test_render_negative_duration_and_invalid_tempo() {
+        let synth = BassSynth::new(SAMPLE_RATE);
+        // 長さが負の音は長さ 0（ミュートだけ鳴る）
+        let samples = synth
+            .render_events(&[NoteEvent::new(5, 2.0, -1.5)], 120.0)
+            .unwrap();
+        assert_eq!(samples.len(), (1.05 * SAMPLE_RATE as f64).ceil() as usize);
+        let samples = synth
+            .render(&[note(55.0, -1.0, -1.0), note(55.0, 0.5, 0.5)])
+            .unwrap();
+        assert_eq!(samples.len(), (1.05 * SAMPLE_RATE as f64).ceil() as usize);
+
+        let pattern = FingeringPattern::new(vec![FretPosition::new(4, 0)], "test".to_string());
+        for bpm in [0.0, -120.0, f32::NAN, f32::INFINITY] {
+            let events = [NoteEvent::new(5, 0.0, 1.0)];
+            assert_eq!(
+                synth.render_events(&events, bpm).unwrap_err().code(),
+                "INVALID_INPUT"
+            );
+            assert_eq!(
+                synth
+                    .render_pattern(&pattern, &Tuning::bass_4(), None, bpm)
+                    .unwrap_err()
+                    .code(),
+                "INVALID_INPUT"
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_rejects_unbounded_notes() {
+        let synth = BassSynth::new(SAMPLE_RATE);
+        let invalid = |notes: &[SynthNote]| synth.render(notes).unwrap_err().code();
+        assert_eq!(invalid(&[note(55.0, f64::INFINITY, 1.0)]), "INVALID_INPUT");
+        assert_eq!(invalid(&[note(55.0, 0.0, f64::NAN)]), "INVALID_INPUT");
+        // 長すぎる合成は確保する前にエラー
+        assert_eq!(invalid(&[note(55.0, 1e12, 1.0)]), "INVALID_INPUT");
+        let events = [NoteEvent::new(5, 1e12, 1.0)];
+        assert_eq!(
+            synth.render_events(&events, 120.0).unwrap_err().code(),
+            "INVALID_INPUT"
+        );
+        // 1周期が数サンプルしかない高い音は調律できない
+        assert_eq!(invalid(&[note(20000.0, 0.0, 1.0)]), "INVALID_INPUT");
+        assert!(synth.render(&[note(5000.0, 0.0, 0.1)]).is_ok());
+    }
+
+    #[test]
+    fn test_string_timbre() {
+        let lowest = StringTimbre::for_string(5, 5);
+        let highest = StringTimbre::for_string(1, 5);
+        assert!(lowest.brightness < highest.brightness);
+        assert!(lowest.sustain > highest.sustain);
+        assert_eq!(
+            StringTimbre::for_string(1, 1),
+            StringTimbre::for_string(9, 1)
+        );
+    }
+
+    #[test]
+    fn test_encode_wav() {
+        let wav = encode_wav(&[0.0, 1.0, -1.0, 0.5], 44100);
+        assert_eq!(wav.len(), 44 + 8);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(wav[4..8].try_into().unwrap()), 36 + 8);
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u16::from_le_bytes([wav[22], wav[23]]), 1); // モノラル
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 44100);
+        assert_eq!(u16::from_le_bytes([wav[34], wav[35]]), 16);
+        assert_eq!(&wav[36..40], b"data");
+        let samples: Vec<i16> = wav[44..]
+            .chunks(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        assert_eq!(samples, [0, 32767, -32767, 16384]);
+    }
+}
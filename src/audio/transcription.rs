@@ -0,0 +1,488 @@
+//! 単音のベースラインの採譜（録音 → 音符 → 運指）
+//! 音量の立ち上がりと無音で区間に分け、区間内のピッチの変化（ハンマリング・スライド）でさらに分ける
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use super::pitch_detection::{rms, PitchDetector, SILENCE_RMS};
+use crate::core::frequency::{parse_reference, ReferencePitch};
+use crate::core::pitch::{Pitch, E1_ABSOLUTE_SEMITONE};
+use crate::error::{to_js, SidFretError};
+use crate::instrument::fingering::algorithm::{parse_mode, parse_tuning};
+use crate::instrument::fingering::position::FingeringPattern;
+use crate::instrument::fingering::rhythm::{
+    calculate_rhythmic, parse_bpm, seconds_to_beats, NoteEvent,
+};
+use crate::instrument::fingering::scoring::AlgorithmWeights;
+use crate::instrument::tuning::Tuning;
+
+/// 音量とピッチを調べる間隔（秒）
+const HOP_SECONDS: f64 = 0.01;
+/// ピッチを検出する間隔（hop 数、間の位置は直前の検出結果を使う）
+const PITCH_STRIDE: usize = 3;
+/// これより短い音は雑音として捨てる（秒）
+const MIN_NOTE_SECONDS: f64 = 0.06;
+/// 直前の音量の何倍になったら新しい発音とみなすか
+const ONSET_RATIO: f64 = 2.0;
+
+/// 採譜した音（時間の単位は秒）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TranscribedNote {
+    pub pitch: Pitch,
+    /// 発音開始（秒）
+    pub onset: f64,
+    /// 長さ（秒）
+    pub duration: f64,
+    /// 検出した周波数の中央値（Hz）
+    pub frequency: f64,
+    /// ピッチ検出の確からしさの平均（0〜1）
+    pub confidence: f64,
+}
+
+impl TranscribedNote {
+    /// テンポを指定して運指計算用の音（拍単位、E1=0基準）に変換（bpm は parse_bpm で検証済みの値）
+    pub fn to_note_event(&self, bpm: f32) -> NoteEvent {
+        NoteEvent::new(
+            self.pitch.semitone() - E1_ABSOLUTE_SEMITONE,
+            seconds_to_beats(self.onset as f32, bpm),
+            seconds_to_beats(self.duration as f32, bpm),
+        )
+    }
+}
+
+/// 区間内の1回分のピッチ検出
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    semitone: i32,
+    frequency: f64,
+    confidence: f64,
+}
+
+/// 単音の録音を音符に分ける
+#[derive(Debug, Clone, Copy)]
+pub struct Transcriber {
+    detector: PitchDetector,
+    reference: ReferencePitch,
+}
+
+impl Transcriber {
+    /// サンプリング周波数を指定して生成（正の有限値でない場合は None）
+    pub fn new(sample_rate: f64) -> Option<Self> {
+        Some(Self {
+            detector: PitchDetector::new(sample_rate)?,
+            reference: ReferencePitch::STANDARD,
+        })
+    }
+
+    pub fn with_reference(mut self, reference: ReferencePitch) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    /// ピッチ検出器を変更（サンプリング周波数も検出器のものを使う）
+    pub fn with_detector(mut self, detector: PitchDetector) -> Self {
+        self.detector = detector;
+        self
+    }
+
+    fn sample_rate(&self) -> f64 {
+        self.detector.sample_rate()
+    }
+
+    fn hop(&self) -> usize {
+        ((self.sample_rate() * HOP_SECONDS).round() as usize).max(1)
+    }
+
+    /// 録音を音符の列に変換（発音順）
+    pub fn transcribe(&self, samples: &[f32]) -> Vec<TranscribedNote> {
+        let hop = self.hop();
+        let period = (self.sample_rate() / self.detector.min_frequency()).ceil() as usize;
+        let min_hops = ((MIN_NOTE_SECONDS / HOP_SECONDS).ceil() as usize).max(1);
+
+        let mut notes = Vec::new();
+        for (start, end) in sounding_segments(samples, hop, period) {
+            let frames = self.segment_frames(samples, start, end);
+            for (run_start, run_end, run) in pitch_runs(&frames, start, end, min_hops) {
+                if run_end - run_start < min_hops {
+                    continue;
+                }
+                let mut frequencies: Vec<f64> = run.iter().map(|f| f.frequency).collect();
+                frequencies.sort_by(f64::total_cmp);
+                let confidence = run.iter().map(|f| f.confidence).sum::<f64>() / run.len() as f64;
+                notes.push(TranscribedNote {
+                    pitch: Pitch::from_semitone(run[0].semitone),
+                    onset: (run_start * hop) as f64 / self.sample_rate(),
+                    duration: ((run_end - run_start) * hop) as f64 / self.sample_rate(),
+                    frequency: frequencies[frequencies.len() / 2],
+                    confidence,
+                });
+            }
+        }
+        notes
+    }
+
+    /// 区間 [start, end)（hop 単位）の各位置のピッチ
+    /// 前後の音が混ざらないよう、区間内に収まる検出窓だけを使う（区間が窓より短ければ区間全体で1回）
+    fn segment_frames(&self, samples: &[f32], start: usize, end: usize) -> Vec<Option<Frame>> {
+        let hop = self.hop();
+        let from = start * hop;
+        let to = (end * hop).min(samples.len());
+        let window = self.detector.min_samples();
+
+        if to - from <= window {
+            let frame = self.frame(&samples[from..to]);
+            return vec![frame; end - start];
+        }
+        let mut frames = Vec::with_capacity(end - start);
+        for h in start..end {
+            if !(h - start).is_multiple_of(PITCH_STRIDE) {
+                frames.push(frames.last().copied().flatten());
+                continue;
+            }
+            let center = h * hop + hop / 2;
+            let frame = center
+                .checked_sub(window / 2)
+                .filter(|&left| left >= from && left + window <= to)
+                .and_then(|left| self.frame(&samples[left..left + window]));
+            frames.push(frame);
+        }
+        frames
+    }
+
+    fn frame(&self, samples: &[f32]) -> Option<Frame> {
+        let detection = self.detector.detect(samples)?;
+        let nearest = self.reference.nearest_pitch(detection.frequency)?;
+        Some(Frame {
+            semitone: nearest.pitch.semitone(),
+            frequency: detection.frequency,
+            confidence: detection.confidence,
+        })
+    }
+}
+
+/// 音が鳴っている区間（hop 単位の [start, end)）を、無音と音量の立ち上がりで区切る
+/// 音量は最低周波数の1周期以上の窓で測り、各位置の後ろの窓と前の窓の比が最大になる位置を発音とする
+fn sounding_segments(samples: &[f32], hop: usize, window: usize) -> Vec<(usize, usize)> {
+    let hops = samples.len().div_ceil(hop);
+    let level =
+        |from: usize, to: usize| rms(&samples[from.min(samples.len())..to.min(samples.len())]);
+    let after: Vec<f64> = (0..hops)
+        .map(|h| level(h * hop, h * hop + window))
+        .collect();
+    let rise: Vec<f64> = (0..hops)
+        .map(|h| {
+            let before = level((h * hop).saturating_sub(window), h * hop);
+            after[h] / before.max(SILENCE_RMS)
+        })
+        .collect();
+
+    // 立ち上がりが続く範囲ごとに比が最大の位置を発音とする
+    let mut onsets: Vec<usize> = Vec::new();
+    let mut h = 0;
+    while h < hops {
+        if rise[h] > ONSET_RATIO && after[h] >= SILENCE_RMS {
+            let mut end = h;
+            while end + 1 < hops && rise[end + 1] > ONSET_RATIO {
+                end += 1;
+            }
+            let peak = (h..=end)
+                .max_by(|&a, &b| rise[a].total_cmp(&rise[b]))
+                .unwrap_or(h);
+            onsets.push(peak);
+            h = end + 1;
+        } else {
+            h += 1;
+        }
+    }
+
+    // 発音から次の発音、または無音になるまで
+    onsets
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let next = onsets.get(i + 1).copied().unwrap_or(hops);
+            let end = (start + 1..next)
+                .find(|&h| after[h] < SILENCE_RMS)
+                .unwrap_or(next);
+            (start, end)
+        })
+        .collect()
+}
+
+/// 区間内のピッチが続く範囲（hop 単位の [start, end) と検出結果）
+/// 短い揺れは前後の音に含め、ピッチが変わる境目は前の音の最後の検出と次の音の最初の検出の中間に置く
+fn pitch_runs(
+    frames: &[Option<Frame>],
+    start: usize,
+    end: usize,
+    min_hops: usize,
+) -> Vec<(usize, usize, Vec<Frame>)> {
+    // (最初の位置, 最後の位置, 検出結果)
+    let mut runs: Vec<(usize, usize, Vec<Frame>)> = Vec::new();
+    for (offset, frame) in frames.iter().enumerate() {
+        let Some(frame) = *frame else {
+            continue;
+        };
+        let h = start + offset;
+        match runs.last_mut() {
+            Some((_, last, run)) if run[0].semitone == frame.semitone => {
+                *last = h;
+                run.push(frame);
+            }
+            _ => runs.push((h, h, vec![frame])),
+        }
+    }
+
+    // 検出回数の少ない揺れを捨て、同じ音が続けば一つにまとめる
+    let mut stable: Vec<(usize, usize, Vec<Frame>)> = Vec::new();
+    for run in runs {
+        if run.2.len() < min_hops {
+            continue;
+        }
+        match stable.last_mut() {
+            Some(last) if last.2[0].semitone == run.2[0].semitone => {
+                last.1 = run.1;
+                last.2.extend(run.2);
+            }
+            _ => stable.push(run),
+        }
+    }
+
+    let boundaries: Vec<usize> = stable
+        .windows(2)
+        .map(|pair| (pair[0].1 + pair[1].0).div_ceil(2))
+        .collect();
+    stable
+        .into_iter()
+        .enumerate()
+        .map(|(i, (_, _, run))| {
+            let run_start = if i == 0 { start } else { boundaries[i - 1] };
+            let run_end = boundaries.get(i).copied().unwrap_or(end);
+            (run_start, run_end, run)
+        })
+        .collect()
+}
+
+/// 採譜と運指の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transcription {
+    pub notes: Vec<TranscribedNote>,
+    /// 運指計算に渡した音（拍単位）
+    pub events: Vec<NoteEvent>,
+    pub pattern: FingeringPattern,
+}
+
+/// 録音を採譜し、リズムとテンポを考慮した運指を付ける（音域外の音・不正なテンポはエラー）
+pub fn transcribe_with_fingering(
+    samples: &[f32],
+    transcriber: &Transcriber,
+    bpm: f32,
+    tuning: &Tuning,
+    weights: &AlgorithmWeights,
+) -> Result<Transcription, SidFretError> {
+    let bpm = parse_bpm(bpm)?;
+    let notes = transcriber.transcribe(samples);
+    let events: Vec<NoteEvent> = notes.iter().map(|note| note.to_note_event(bpm)).collect();
+    let pattern = if events.is_empty() {
+        FingeringPattern::new(vec![], "rhythmic".to_string())
+    } else {
+        calculate_rhythmic(&events, bpm, tuning, weights)?
+    };
+    Ok(Transcription {
+        notes,
+        events,
+        pattern,
+    })
+}
+
+/// WASM公開API: 録音（モノラルの `Float32Array`）を採譜して運指を付ける（`Transcription` を返す）
+#[wasm_bindgen]
+pub fn transcribe_bass_line(
+    samples: &[f32],
+    sample_rate: f64,
+    bpm: f32,
+    mode: &str,
+    tuning_name: &str,
+    a4: f64,
+) -> Result<JsValue, JsValue> {
+    let transcriber = Transcriber::new(sample_rate)
+        .ok_or_else(|| SidFretError::InvalidInput(format!("invalid sample rate {sample_rate}")))?;
+    let transcriber = transcriber.with_reference(parse_reference(a4)?);
+    let fingering_mode = parse_mode(mode)?;
+    let tuning = parse_tuning(tuning_name)?;
+
+    let transcription = transcribe_with_fingering(
+        samples,
+        &transcriber,
+        bpm,
+        &tuning,
+        &fingering_mode.weights(),
+    )?;
+    Ok(to_js(&transcription)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+
+    const SAMPLE_RATE: f64 = 8000.0;
+
+    /// 減衰する倍音付きの音を連ねた信号（(周波数, 開始秒, 長さ秒)、周波数 0 は無音）
+    fn plucked_line(notes: &[(f64, f64, f64)]) -> Vec<f32> {
+        let total = notes.iter().map(|&(_, s, d)| s + d).fold(0.0, f64::max);
+        let mut samples = vec![0.0f32; (total * SAMPLE_RATE) as usize];
+        for &(frequency, start, duration) in notes {
+            let from = (start * SAMPLE_RATE) as usize;
+            let to = ((start + duration) * SAMPLE_RATE) as usize;
+            for (i, sample) in samples[from..to].iter_mut().enumerate() {
+                let t = i as f64 / SAMPLE_RATE;
+                let tone: f64 = [1.0, 0.6, 0.3]
+                    .iter()
+                    .enumerate()
+                    .map(|(h, amp)| amp * (TAU * frequency * (h + 1) as f64 * t).sin())
+                    .sum();
+                *sample = (0.4 * tone * (-t / 0.4).exp()) as f32;
+            }
+        }
+        samples
+    }
+
+    fn transcribe(samples: &[f32]) -> Vec<TranscribedNote> {
+        Transcriber::new(SAMPLE_RATE).unwrap().transcribe(samples)
+    }
+
+    fn names(notes: &[TranscribedNote]) -> Vec<String> {
+        notes.iter().map(|n| n.pitch.to_string()).collect()
+    }
+
+    #[test]
+    fn test_transcribe_plucked_notes() {
+        // E1, A1, A1（弾き直し）, 休符, D2
+        let samples = plucked_line(&[
+            (41.2, 0.0, 0.5),
+            (55.0, 0.5, 0.5),
+            (55.0, 1.0, 0.5),
+            (73.42, 1.75, 0.5),
+        ]);
+        let notes = transcribe(&samples);
+        assert_eq!(names(&notes), ["E1", "A1", "A1", "D2"]);
+
+        for (note, onset) in notes.iter().zip([0.0, 0.5, 1.0, 1.75]) {
+            assert!((note.onset - onset).abs() <= 0.02, "{note:?}");
+            assert!(note.confidence > 0.8);
+        }
+        // 休符の前の音は無音になるまで
+        assert!((notes[2].duration - 0.5).abs() <= 0.02);
+        assert!((notes[3].frequency - 73.42).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_transcribe_legato_pitch_change() {
+        // 音量の立ち上がりがないピッチ変化（ハンマリング）も別の音にする
+        let mut samples = plucked_line(&[(55.0, 0.0, 0.4)]);
+        let phase_offset = samples.len();
+        samples.extend((0..(0.4 * SAMPLE_RATE) as usize).map(|i| {
+            let t = (phase_offset + i) as f64 / SAMPLE_RATE;
+            (0.4 * (TAU * 61.74 * t).sin() * (-t / 0.4).exp()) as f32
+        }));
+        let notes = transcribe(&samples);
+        assert_eq!(names(&notes), ["A1", "B1"]);
+        assert!((notes[1].onset - 0.4).abs() <= 0.05);
+    }
+
+    #[test]
+    fn test_transcribe_silence_and_clicks() {
+        assert!(transcribe(&vec![0.0; 8000]).is_empty());
+        assert!(transcribe(&[]).is_empty());
+
+        // 短すぎる音は捨てる
+        let samples = plucked_line(&[(55.0, 0.2, 0.03)]);
+        assert!(transcribe(&samples).is_empty());
+    }
+
+    #[test]
+    fn test_transcribe_with_fingering() {
+        let samples = plucked_line(&[(41.2, 0.0, 0.5), (49.0, 0.5, 0.5), (55.0, 1.0, 0.5)]);
+        let transcriber = Transcriber::new(SAMPLE_RATE).unwrap();
+        let result = transcribe_with_fingering(
+            &samples,
+            &transcriber,
+            120.0,
+            &Tuning::bass_4(),
+            &AlgorithmWeights::balanced(),
+        )
+        .unwrap();
+
+        assert_eq!(names(&result.notes), ["E1", "G1", "A1"]);
+        let pitches: Vec<i32> = result.events.iter().map(|e| e.pitch).collect();
+        assert_eq!(pitches, [0, 3, 5]);
+        // 120BPM で 0.5秒 = 1拍
+        assert!((result.events[1].onset - 1.0).abs() < 0.05);
+        assert_eq!(result.pattern.positions.len(), 3);
+
+        let empty = transcribe_with_fingering(
+            &[],
+            &transcriber,
+            120.0,
+            &Tuning::bass_4(),
+            &AlgorithmWeights::balanced(),
+        )
+        .unwrap();
+        assert!(empty.notes.is_empty() && empty.pattern.positions.is_empty());
+    }
+
+    #[test]
+    fn test_transcribe_invalid_tempo_is_error() {
+        let samples = plucked_line(&[(41.2, 0.0, 0.5)]);
+        let transcriber = Transcriber::new(SAMPLE_RATE).unwrap();
+        for bpm in [0.0, -120.0, f32::NAN, f32::INFINITY] {
+            for samples in [&samples[..], &[]] {
+                let result = transcribe_with_fingering(
+                    samples,
+                    &transcriber,
+                    bpm,
+                    &Tuning::bass_4(),
+                    &AlgorithmWeights::balanced(),
+                );
+                assert!(matches!(result, Err(SidFretError::InvalidInput(_))));
+            }
+        }
+    }
+
+    #[test]
+    fn test_detector_sets_sample_rate() {
+        // 検出器のサンプリング周波数で発音位置と長さを秒に変換する
+        let samples = plucked_line(&[(41.2, 0.0, 0.5), (55.0, 0.5, 0.5)]);
+        let detector = PitchDetector::new(SAMPLE_RATE)
+            .unwrap()
+            .with_frequency_range(35.0, 400.0)
+            .unwrap();
+        let transcriber = Transcriber::new(44100.0).unwrap().with_detector(detector);
+        let notes = transcriber.transcribe(&samples);
+        assert_eq!(names(&notes), ["E1", "A1"]);
+        assert!((notes[1].onset - 0.5).abs() <= 0.02, "{notes:?}");
+        assert!((notes[1].duration - 0.5).abs() <= 0.02, "{notes:?}");
+    }
+
+    #[test]
+    fn test_transcribe_out_of_range_is_error() {
+        // 4弦ベースに B0 はない
+        let samples = plucked_line(&[(30.87, 0.0, 0.5)]);
+        let transcriber = Transcriber::new(SAMPLE_RATE).unwrap();
+        let result = transcribe_with_fingering(
+            &samples,
+            &transcriber,
+            120.0,
+            &Tuning::bass_4(),
+            &AlgorithmWeights::balanced(),
+        );
+        assert_eq!(
+            result.unwrap_err(),
+            SidFretError::PitchOutOfRange {
+                index: 0,
+                pitch: -5
+            }
+        );
+    }
+}
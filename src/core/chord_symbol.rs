@@ -0,0 +1,584 @@
+//! コードシンボルの構文解析
+//! "C7(♭9,♭13)" や "Cm11/G" をルート・種類・7th・テンション・オルタレーション・
+//! 追加音・省略音・ベース音に分解する
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+use crate::core::chord_type::{split_root, ChordTone};
+use crate::core::interval::Interval;
+use crate::core::pitch::{Accidental, Letter, Note, PitchClass};
+use crate::error::{to_js, SidFretError};
+
+/// メジャースケール上の各度数の半音数（1〜7度）
+const MAJOR_DEGREES: [i32; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// コードの種類（3度と5度の組み合わせ）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChordQuality {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+    /// 3度の代わりに2度
+    Sus2,
+    /// 3度の代わりに4度
+    Sus4,
+    /// 1度と5度のみ（パワーコード）
+    Power,
+}
+
+/// 7度の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Seventh {
+    /// ♭7（C7, Cm7, Cm7♭5）
+    Minor,
+    /// 7（Cmaj7, Cm(maj7)）
+    Major,
+    /// ♭♭7（Cdim7）
+    Diminished,
+}
+
+/// ルートからの度数（例: "♭9", "＃11", "6"）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChordDegree {
+    pub number: u8,
+    pub accidental: Accidental,
+}
+
+impl ChordDegree {
+    pub fn new(number: u8, accidental: Accidental) -> Self {
+        Self { number, accidental }
+    }
+
+    pub fn natural(number: u8) -> Self {
+        Self::new(number, Accidental::Natural)
+    }
+
+    /// ルートからの半音数（9度以上は1オクターブ上）
+    pub fn semitones(self) -> i32 {
+        let index = (self.number.max(1) - 1) as usize;
+        MAJOR_DEGREES[index % 7] + 12 * (index / 7) as i32 + self.accidental.semitones()
+    }
+}
+
+impl fmt::Display for ChordDegree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.accidental.to_str(), self.number)
+    }
+}
+
+impl FromStr for ChordDegree {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (accidental, len) = Accidental::parse_prefix(s);
+        match s[len..].parse() {
+            Ok(number) if number >= 1 => Ok(Self::new(number, accidental)),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Serialize for ChordDegree {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChordDegree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|_| de::Error::custom(format!("invalid chord degree \"{s}\"")))
+    }
+}
+
+/// 構造化したコードシンボル
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChordSymbol {
+    pub root: Note,
+    pub quality: ChordQuality,
+    pub seventh: Option<Seventh>,
+    /// 変化していないテンション（9, 11, 13）
+    pub extensions: Vec<u8>,
+    /// 変化させた5度・テンション（♭5, ＃5, ♭9, ＃9, ＃11, ♭13）
+    pub alterations: Vec<ChordDegree>,
+    /// 追加音（6, add9 など）
+    pub added: Vec<ChordDegree>,
+    /// 省略した度数（omit3 → 3）
+    pub omitted: Vec<u8>,
+    /// 分数コードのベース音（C/E → E）
+    pub bass: Option<Note>,
+}
+
+impl ChordSymbol {
+    /// ルートとコードタイプ（"m7", "7(♭9)", "/E" など）から解析
+    pub fn from_suffix(root: Note, suffix: &str) -> Result<Self, SidFretError> {
+        let unknown = || SidFretError::UnknownChord(suffix.to_string());
+        let (body, bass) = split_slash_bass(suffix);
+        let mut symbol = SuffixParser::new(body).parse().ok_or_else(unknown)?;
+        symbol.root = root;
+        symbol.bass = bass;
+        Ok(symbol)
+    }
+
+    /// 分数コードのベース音のルートからの度数（C/E → 3、C/B♭ → ♭7、分数コードでない場合は None）
+    pub fn bass_degree(&self) -> Option<ChordDegree> {
+        let interval = Interval::between_notes(self.root, self.bass?);
+        let number = interval.number() as u8;
+        let natural = MAJOR_DEGREES[(number - 1) as usize];
+        let accidental = Accidental::from_semitones(interval.semitones() - natural)?;
+        Some(ChordDegree::new(number, accidental))
+    }
+
+    /// ベース音が何番目の構成音か（0 = 基本形、1 = 第1転回形、構成音でない場合は None）
+    pub fn inversion(&self, bass: PitchClass) -> Option<usize> {
+        let root = self.root.pitch_class();
+        self.tones()
+            .iter()
+            .position(|tone| root.transpose(tone.semitones) == bass)
+    }
+
+    /// 構成音（度数の低い順、省略音を除く）
+    pub fn tones(&self) -> Vec<ChordTone> {
+        let third = match self.quality {
+            ChordQuality::Major | ChordQuality::Augmented => Some(ChordDegree::natural(3)),
+            ChordQuality::Minor | ChordQuality::Diminished => {
+                Some(ChordDegree::new(3, Accidental::Flat))
+            }
+            ChordQuality::Sus2 => Some(ChordDegree::natural(2)),
+            ChordQuality::Sus4 => Some(ChordDegree::natural(4)),
+            ChordQuality::Power => None,
+        };
+        let fifth = match self.quality {
+            ChordQuality::Diminished => ChordDegree::new(5, Accidental::Flat),
+            ChordQuality::Augmented => ChordDegree::new(5, Accidental::Sharp),
+            _ => ChordDegree::natural(5),
+        };
+        // 5度のオルタレーションは元の5度と置き換える
+        let fifth = (!self.alterations.iter().any(|d| d.number == 5)).then_some(fifth);
+        let seventh = self.seventh.map(|seventh| match seventh {
+            Seventh::Minor => ChordDegree::new(7, Accidental::Flat),
+            Seventh::Major => ChordDegree::natural(7),
+            Seventh::Diminished => ChordDegree::new(7, Accidental::DoubleFlat),
+        });
+
+        let mut degrees: Vec<ChordDegree> = [Some(ChordDegree::natural(1)), third, fifth, seventh]
+            .into_iter()
+            .flatten()
+            .chain(self.extensions.iter().map(|&n| ChordDegree::natural(n)))
+            .chain(self.alterations.iter().copied())
+            .chain(self.added.iter().copied())
+            .filter(|d| !self.omitted.contains(&d.number))
+            .collect();
+        degrees.sort_by_key(|d| (d.number, d.semitones()));
+        degrees.dedup();
+
+        degrees
+            .into_iter()
+            .map(|degree| ChordTone {
+                interval: degree.to_string(),
+                semitones: degree.semitones(),
+            })
+            .collect()
+    }
+}
+
+impl FromStr for ChordSymbol {
+    type Err = SidFretError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || SidFretError::UnknownChord(s.to_string());
+        let (root, suffix) = split_root(s).ok_or_else(unknown)?;
+        Self::from_suffix(root, suffix).map_err(|_| unknown())
+    }
+}
+
+/// コード名を分数コードのベース音とそれ以外に分ける（"C/E" → ("C", E)）
+/// "C6/9" の "/9" は音名ではないので分数コードではない
+pub fn split_slash_bass(chord: &str) -> (&str, Option<Note>) {
+    match chord.rsplit_once('/') {
+        Some((body, bass)) => match bass.parse() {
+            Ok(bass) => (body, Some(bass)),
+            Err(()) => (chord, None),
+        },
+        None => (chord, None),
+    }
+}
+
+/// コードタイプ部分（ルートとベース音を除いた文字列）の解析
+struct SuffixParser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> SuffixParser<'a> {
+    fn new(body: &'a str) -> Self {
+        Self { rest: body }
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        match self.rest.strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn eat_any(&mut self, tokens: &[&str]) -> Option<usize> {
+        tokens.iter().position(|token| self.eat(token))
+    }
+
+    fn number(&mut self) -> Option<u8> {
+        let len = self
+            .rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        let number = self.rest[..len].parse().ok()?;
+        self.rest = &self.rest[len..];
+        Some(number)
+    }
+
+    /// オルタレーション・追加音の変化記号（"-5" "+5" も受け付ける）
+    fn accidental(&mut self) -> Option<Accidental> {
+        if self.eat_any(&["♭", "b", "-"]).is_some() {
+            Some(Accidental::Flat)
+        } else if self.eat_any(&["＃", "♯", "#", "+"]).is_some() {
+            Some(Accidental::Sharp)
+        } else {
+            None
+        }
+    }
+
+    fn parse(mut self) -> Option<ChordSymbol> {
+        if self.rest == "5" {
+            return Some(symbol(ChordQuality::Power));
+        }
+
+        let mut half_diminished = false;
+        let mut quality = if self.eat_any(&["dim", "o", "°"]).is_some() {
+            ChordQuality::Diminished
+        } else if self.eat("ø") {
+            half_diminished = true;
+            ChordQuality::Diminished
+        } else if self.eat_any(&["aug", "+"]).is_some() {
+            ChordQuality::Augmented
+        } else if self.eat("min")
+            || (!self.rest.starts_with("maj") && self.eat_any(&["m", "-"]).is_some())
+        {
+            ChordQuality::Minor
+        } else {
+            ChordQuality::Major
+        };
+
+        // 6, 69, 7, 9, 11, 13 のいずれか（maj7 系は major_seventh）
+        let mut size: Option<u8> = None;
+        let mut major_seventh = false;
+        let mut alterations: Vec<ChordDegree> = vec![];
+        let mut added: Vec<ChordDegree> = vec![];
+        let mut omitted: Vec<u8> = vec![];
+
+        while !self.rest.is_empty() {
+            if self.eat_any(&["(", ")", ",", " ", "_"]).is_some() {
+                continue;
+            }
+            if let Some(marker) = self.eat_any(&["maj", "Maj", "M", "△", "Δ"]) {
+                if size.is_some() || major_seventh {
+                    return None;
+                }
+                match self.number() {
+                    Some(n @ (7 | 9 | 11 | 13)) => {
+                        size = Some(n);
+                        major_seventh = true;
+                    }
+                    // "△" だけは maj7、"maj" "M" だけはメジャートライアド
+                    None if marker >= 3 => {
+                        size = Some(7);
+                        major_seventh = true;
+                    }
+                    None => {}
+                    Some(_) => return None,
+                }
+            } else if self.eat("sus") {
+                if quality != ChordQuality::Major {
+                    return None;
+                }
+                quality = match self.number() {
+                    None | Some(4) => ChordQuality::Sus4,
+                    Some(2) => ChordQuality::Sus2,
+                    Some(_) => return None,
+                };
+            } else if self.eat("add") {
+                let accidental = self.accidental().unwrap_or(Accidental::Natural);
+                match self.number()? {
+                    n @ (2 | 4 | 6 | 9 | 11 | 13) => added.push(ChordDegree::new(n, accidental)),
+                    _ => return None,
+                }
+            } else if self.eat_any(&["omit", "no"]).is_some() {
+                match self.number()? {
+                    n @ (3 | 5) => omitted.push(n),
+                    _ => return None,
+                }
+            } else if let Some(accidental) = self.accidental() {
+                match self.number()? {
+                    n @ (5 | 9 | 11 | 13) => alterations.push(ChordDegree::new(n, accidental)),
+                    _ => return None,
+                }
+            } else {
+                let n = self.number()?;
+                if size.is_some() || !matches!(n, 6 | 69 | 7 | 9 | 11 | 13) {
+                    return None;
+                }
+                size = Some(if n == 6 && self.eat("/9") { 69 } else { n });
+            }
+        }
+
+        let mut chord = symbol(quality);
+        chord.seventh = match size {
+            Some(7 | 9 | 11 | 13) if major_seventh => Some(Seventh::Major),
+            Some(7 | 9 | 11 | 13) if quality == ChordQuality::Diminished && !half_diminished => {
+                Some(Seventh::Diminished)
+            }
+            Some(7 | 9 | 11 | 13) => Some(Seventh::Minor),
+            _ if half_diminished => Some(Seventh::Minor),
+            _ => None,
+        };
+        chord.extensions = match size {
+            Some(9) => vec![9],
+            Some(11) => vec![9, 11],
+            // 長3度（sus4 の4度）と短9度でぶつかる11度は省く
+            Some(13) if matches!(quality, ChordQuality::Minor | ChordQuality::Diminished) => {
+                vec![9, 11, 13]
+            }
+            Some(13) => vec![9, 13],
+            _ => vec![],
+        };
+        if size == Some(6) || size == Some(69) {
+            chord.added.push(ChordDegree::natural(6));
+        }
+        if size == Some(69) {
+            chord.added.push(ChordDegree::natural(9));
+        }
+        // オルタレーションは同じ度数のテンションと置き換える
+        chord
+            .extensions
+            .retain(|&n| !alterations.iter().any(|d| d.number == n));
+        chord.alterations = alterations;
+        chord.added.extend(added);
+        chord.omitted = omitted;
+        Some(chord)
+    }
+}
+
+fn symbol(quality: ChordQuality) -> ChordSymbol {
+    ChordSymbol {
+        root: Note::new(Letter::C, Accidental::Natural),
+        quality,
+        seventh: None,
+        extensions: vec![],
+        alterations: vec![],
+        added: vec![],
+        omitted: vec![],
+        bass: None,
+    }
+}
+
+/// WASM公開API: コードシンボルを解析（`ChordSymbol`、解釈できない場合は例外）
+#[wasm_bindgen]
+pub fn parse_chord_symbol(chord: &str) -> Result<JsValue, JsValue> {
+    let symbol: ChordSymbol = chord.parse()?;
+    Ok(to_js(&symbol)?)
+}
+
+/// WASM公開API: コードシンボルの構成音（`ChordTone[]`、解釈できない場合は例外）
+#[wasm_bindgen]
+pub fn get_chord_symbol_tones(chord: &str) -> Result<JsValue, JsValue> {
+    let symbol: ChordSymbol = chord.parse()?;
+    Ok(to_js(&symbol.tones())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(chord: &str) -> ChordSymbol {
+        chord.parse().unwrap_or_else(|e| panic!("{chord}: {e}"))
+    }
+
+    fn intervals(chord: &str) -> Vec<String> {
+        parse(chord)
+            .tones()
+            .into_iter()
+            .map(|t| t.interval)
+            .collect()
+    }
+
+    fn semitones(chord: &str) -> Vec<i32> {
+        parse(chord)
+            .tones()
+            .into_iter()
+            .map(|t| t.semitones)
+            .collect()
+    }
+
+    fn degree(s: &str) -> ChordDegree {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_extensions() {
+        let c13 = parse("C13");
+        assert_eq!(c13.quality, ChordQuality::Major);
+        assert_eq!(c13.seventh, Some(Seventh::Minor));
+        assert_eq!(c13.extensions, vec![9, 13]);
+        assert_eq!(intervals("C13"), ["1", "3", "5", "♭7", "9", "13"]);
+        assert_eq!(semitones("C13"), [0, 4, 7, 10, 14, 21]);
+
+        let cm11 = parse("Cm11");
+        assert_eq!(cm11.quality, ChordQuality::Minor);
+        assert_eq!(cm11.extensions, vec![9, 11]);
+        assert_eq!(semitones("Cm11"), [0, 3, 7, 10, 14, 17]);
+        assert_eq!(intervals("Cm13"), ["1", "♭3", "5", "♭7", "9", "11", "13"]);
+        assert_eq!(intervals("Cmaj13"), ["1", "3", "5", "7", "9", "13"]);
+        assert_eq!(intervals("C△"), ["1", "3", "5", "7"]);
+    }
+
+    #[test]
+    fn test_alterations() {
+        let chord = parse("C7♯11");
+        assert_eq!(chord.seventh, Some(Seventh::Minor));
+        assert_eq!(chord.alterations, vec![degree("＃11")]);
+        assert_eq!(semitones("C7♯11"), [0, 4, 7, 10, 18]);
+
+        let chord = parse("C7(♭9,♭13)");
+        assert_eq!(chord.alterations, vec![degree("♭9"), degree("♭13")]);
+        assert_eq!(intervals("C7(♭9,♭13)"), ["1", "3", "5", "♭7", "♭9", "♭13"]);
+
+        // オルタレーションは同じ度数のテンション・5度と置き換わる
+        let chord = parse("C13b9");
+        assert_eq!(chord.extensions, vec![13]);
+        assert_eq!(intervals("C13b9"), ["1", "3", "5", "♭7", "♭9", "13"]);
+        assert_eq!(intervals("C7+5"), ["1", "3", "＃5", "♭7"]);
+        assert_eq!(intervals("C7(#5,#9)"), ["1", "3", "＃5", "♭7", "＃9"]);
+        assert_eq!(intervals("Cm7♭5"), ["1", "♭3", "♭5", "♭7"]);
+    }
+
+    #[test]
+    fn test_added_and_omitted() {
+        let chord = parse("C6/9");
+        assert_eq!(chord.bass, None);
+        assert_eq!(chord.added, vec![degree("6"), degree("9")]);
+        assert_eq!(semitones("C6/9"), [0, 4, 7, 9, 14]);
+        assert_eq!(semitones("C69"), semitones("C6/9"));
+
+        let chord = parse("C(omit3)");
+        assert_eq!(chord.omitted, vec![3]);
+        assert_eq!(intervals("C(omit3)"), ["1", "5"]);
+        assert_eq!(intervals("C7no5"), ["1", "3", "♭7"]);
+        assert_eq!(intervals("Cmadd9"), ["1", "♭3", "5", "9"]);
+        assert_eq!(intervals("C7(add13)"), ["1", "3", "5", "♭7", "13"]);
+    }
+
+    #[test]
+    fn test_slash_bass() {
+        let chord = parse("C/E");
+        assert_eq!(chord.quality, ChordQuality::Major);
+        assert_eq!(chord.bass, Some("E".parse().unwrap()));
+        assert_eq!(intervals("C/E"), ["1", "3", "5"]);
+
+        let chord = parse("F＃m7/C#");
+        assert_eq!(chord.root.to_string(), "F＃");
+        assert_eq!(chord.seventh, Some(Seventh::Minor));
+        assert_eq!(chord.bass.unwrap().to_string(), "C＃");
+        // 構成音以外のベース音も受け付ける
+        assert_eq!(parse("Am7/Bb").bass.unwrap().to_string(), "B♭");
+    }
+
+    #[test]
+    fn test_bass_degree_and_inversion() {
+        let degree = |chord: &str| parse(chord).bass_degree().map(|d| d.to_string());
+        assert_eq!(degree("C/E").as_deref(), Some("3"));
+        assert_eq!(degree("Cm/E♭").as_deref(), Some("♭3"));
+        assert_eq!(degree("C7/B♭").as_deref(), Some("♭7"));
+        assert_eq!(degree("D/F＃").as_deref(), Some("3"));
+        assert_eq!(degree("C/D").as_deref(), Some("2"));
+        assert_eq!(degree("C"), None);
+
+        let pc = |name: &str| name.parse::<PitchClass>().unwrap();
+        let g7 = parse("G7");
+        assert_eq!(g7.inversion(pc("G")), Some(0));
+        assert_eq!(g7.inversion(pc("B")), Some(1));
+        assert_eq!(g7.inversion(pc("D")), Some(2));
+        assert_eq!(g7.inversion(pc("F")), Some(3));
+        assert_eq!(g7.inversion(pc("C")), None);
+        // 異名同音も同じ構成音
+        assert_eq!(parse("A♭").inversion(pc("B＃")), Some(1));
+
+        assert_eq!(split_slash_bass("Am7/G"), ("Am7", "G".parse().ok()));
+        assert_eq!(split_slash_bass("C6/9"), ("C6/9", None));
+    }
+
+    #[test]
+    fn test_quality_spellings() {
+        for (chord, quality, seventh) in [
+            ("Cdim7", ChordQuality::Diminished, Some(Seventh::Diminished)),
+            ("C°7", ChordQuality::Diminished, Some(Seventh::Diminished)),
+            ("Cø", ChordQuality::Diminished, Some(Seventh::Minor)),
+            ("Cø7", ChordQuality::Diminished, Some(Seventh::Minor)),
+            ("C+7", ChordQuality::Augmented, Some(Seventh::Minor)),
+            ("CmM7", ChordQuality::Minor, Some(Seventh::Major)),
+            ("Cm(maj7)", ChordQuality::Minor, Some(Seventh::Major)),
+            ("Cm_maj7", ChordQuality::Minor, Some(Seventh::Major)),
+            ("C-M7", ChordQuality::Minor, Some(Seventh::Major)),
+            ("Cmin7", ChordQuality::Minor, Some(Seventh::Minor)),
+            ("Cmaj", ChordQuality::Major, None),
+            ("C7sus", ChordQuality::Sus4, Some(Seventh::Minor)),
+            ("Csus2", ChordQuality::Sus2, None),
+            ("C5", ChordQuality::Power, None),
+        ] {
+            let symbol = parse(chord);
+            assert_eq!(
+                (symbol.quality, symbol.seventh),
+                (quality, seventh),
+                "{chord}"
+            );
+        }
+        assert_eq!(intervals("C5"), ["1", "5"]);
+        assert_eq!(intervals("C13sus4"), ["1", "4", "5", "♭7", "9", "13"]);
+    }
+
+    #[test]
+    fn test_unknown_symbols_are_errors() {
+        for chord in [
+            "Cxyz",
+            "C7alt",
+            "C8",
+            "C7(♭3)",
+            "Cm7sus4",
+            "Cmaj7maj9",
+            "",
+            "H7",
+            "C/X",
+        ] {
+            assert_eq!(
+                chord.parse::<ChordSymbol>(),
+                Err(SidFretError::UnknownChord(chord.to_string())),
+                "{chord}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_chord_degree() {
+        assert_eq!(degree("♭♭7").semitones(), 9);
+        assert_eq!(degree("＃11").semitones(), 18);
+        assert_eq!(degree("b13").to_string(), "♭13");
+        assert!("x".parse::<ChordDegree>().is_err());
+    }
+}
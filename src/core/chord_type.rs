@@ -1,45 +1,37 @@
 //! コード構成音定義（楽器非依存）
 
+use crate::core::chord_symbol::ChordSymbol;
+use crate::core::pitch::{Accidental, Letter, Note};
+use crate::error::SidFretError;
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 /// コードの構成音（インターバルと半音数のペア）
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ChordTone {
     pub interval: String,
     pub semitones: i32,
 }
 
-/// コード名からルート音を抽出
+/// コード名からルート音を抽出（変化記号は ＃/♭ 表記に揃える）
+/// "C#m7" -> "C＃", "Bbmaj7" -> "B♭"
 #[wasm_bindgen]
 pub fn get_root_note(chord: &str) -> String {
-    let mut root = String::new();
-    let mut chars = chord.chars();
-
-    if let Some(c) = chars.next() {
-        if ('A'..='G').contains(&c) {
-            root.push(c);
-        } else {
-            return String::new();
-        }
-    }
-
-    if let Some(c) = chars.next() {
-        if c == '♭' || c == '＃' {
-            root.push(c);
-        }
-    }
+    split_root(chord).map_or_else(String::new, |(root, _)| root.to_string())
+}
 
-    root
+/// コード名をルート音と残りの文字列に分割（"C#m7" -> (C＃, "m7")）
+pub fn split_root(chord: &str) -> Option<(Note, &str)> {
+    let (root, len) = Note::parse_prefix(chord)?;
+    Some((root, &chord[len..]))
 }
 
 /// コード名からルート音とコードタイプを分離
-/// "Cm7" -> ("C", "m7"), "F＃dim7" -> ("F＃", "dim7")
+/// "Cm7" -> ("C", "m7"), "F＃dim7" -> ("F＃", "dim7"), "C#m7" -> ("C＃", "m7")
 pub fn parse_chord_type(chord: &str) -> (String, String) {
-    let root = get_root_note(chord);
-    if root.is_empty() {
+    let Some((root, chord_type)) = split_root(chord) else {
         return (String::new(), chord.to_string());
-    }
-    let chord_type = &chord[root.len()..];
+    };
     let normalized = match chord_type {
         "M7" | "△7" => "maj7",
         "M9" | "△9" => "maj9",
@@ -56,58 +48,17 @@ pub fn parse_chord_type(chord: &str) -> (String, String) {
         "7sus" => "7sus4",
         "-6" => "m6",
         "7♭9" => "7b9",
-        "7＃9" => "7#9",
+        "7＃9" | "7♯9" => "7#9",
         other => other,
     };
-    (root, normalized.to_string())
+    (root.to_string(), normalized.to_string())
 }
 
-/// コードタイプ文字列から構成音配列を生成
-pub fn get_chord_tones(chord_type: &str) -> Vec<ChordTone> {
-    let intervals: Vec<(&str, i32)> = match chord_type {
-        // トライアド
-        "" | "maj" => vec![("1", 0), ("3", 4), ("5", 7)],
-        "m" => vec![("1", 0), ("♭3", 3), ("5", 7)],
-        "dim" => vec![("1", 0), ("♭3", 3), ("♭5", 6)],
-        "aug" => vec![("1", 0), ("3", 4), ("＃5", 8)],
-        "sus4" => vec![("1", 0), ("4", 5), ("5", 7)],
-        "sus2" => vec![("1", 0), ("2", 2), ("5", 7)],
-
-        // 7th
-        "7" => vec![("1", 0), ("3", 4), ("5", 7), ("♭7", 10)],
-        "m7" => vec![("1", 0), ("♭3", 3), ("5", 7), ("♭7", 10)],
-        "maj7" | "M7" => vec![("1", 0), ("3", 4), ("5", 7), ("7", 11)],
-        "m_maj7" | "mM7" => vec![("1", 0), ("♭3", 3), ("5", 7), ("7", 11)],
-        "dim7" => vec![("1", 0), ("♭3", 3), ("♭5", 6), ("♭♭7", 9)],
-        "m7b5" => vec![("1", 0), ("♭3", 3), ("♭5", 6), ("♭7", 10)],
-        "aug7" => vec![("1", 0), ("3", 4), ("＃5", 8), ("♭7", 10)],
-        "7sus4" => vec![("1", 0), ("4", 5), ("5", 7), ("♭7", 10)],
-
-        // 6th
-        "6" => vec![("1", 0), ("3", 4), ("5", 7), ("6", 9)],
-        "m6" => vec![("1", 0), ("♭3", 3), ("5", 7), ("6", 9)],
-
-        // 9th
-        "9" => vec![("1", 0), ("3", 4), ("5", 7), ("♭7", 10), ("9", 14)],
-        "m9" => vec![("1", 0), ("♭3", 3), ("5", 7), ("♭7", 10), ("9", 14)],
-        "maj9" | "M9" => vec![("1", 0), ("3", 4), ("5", 7), ("7", 11), ("9", 14)],
-        "add9" => vec![("1", 0), ("3", 4), ("5", 7), ("9", 14)],
-
-        // Altered
-        "7b9" => vec![("1", 0), ("3", 4), ("5", 7), ("♭7", 10), ("♭9", 13)],
-        "7#9" => vec![("1", 0), ("3", 4), ("5", 7), ("♭7", 10), ("＃9", 15)],
-
-        // フォールバック: メジャートライアド
-        _ => vec![("1", 0), ("3", 4), ("5", 7)],
-    };
-
-    intervals
-        .into_iter()
-        .map(|(interval, semitones)| ChordTone {
-            interval: interval.to_string(),
-            semitones,
-        })
-        .collect()
+/// コードタイプ文字列から構成音配列を生成（"m7", "13", "7(♭9,♭13)", "6/9" など）
+/// 解釈できないコードタイプはエラー
+pub fn get_chord_tones(chord_type: &str) -> Result<Vec<ChordTone>, SidFretError> {
+    let root = Note::new(Letter::C, Accidental::Natural);
+    Ok(ChordSymbol::from_suffix(root, chord_type)?.tones())
 }
 
 /// 12音すべての ChordTone（ALL_KEYS用）
@@ -157,17 +108,17 @@ mod tests {
 
     #[test]
     fn test_get_chord_tones() {
-        let tones = get_chord_tones("");
+        let tones = get_chord_tones("").unwrap();
         assert_eq!(tones.len(), 3);
         assert_eq!(tones[0].interval, "1");
         assert_eq!(tones[1].interval, "3");
         assert_eq!(tones[1].semitones, 4);
 
-        let tones = get_chord_tones("m7");
+        let tones = get_chord_tones("m7").unwrap();
         assert_eq!(tones.len(), 4);
         assert_eq!(tones[1].semitones, 3); // ♭3
 
-        let tones = get_chord_tones("dim7");
+        let tones = get_chord_tones("dim7").unwrap();
         assert_eq!(tones.len(), 4);
         assert_eq!(tones[3].semitones, 9); // ♭♭7
     }
@@ -178,7 +129,7 @@ mod tests {
     #[test]
     fn test_spec_all_22_chord_tones() {
         fn semitones(ct: &str) -> Vec<i32> {
-            get_chord_tones(ct).iter().map(|t| t.semitones).collect()
+            get_chord_tones(ct).unwrap().iter().map(|t| t.semitones).collect()
         }
         // トライアド
         assert_eq!(semitones(""), vec![0, 4, 7]);
@@ -213,7 +164,7 @@ mod tests {
     #[test]
     fn test_spec_chord_tone_intervals() {
         fn intervals(ct: &str) -> Vec<String> {
-            get_chord_tones(ct).iter().map(|t| t.interval.clone()).collect()
+            get_chord_tones(ct).unwrap().iter().map(|t| t.interval.clone()).collect()
         }
         assert_eq!(intervals("m7"), vec!["1", "♭3", "5", "♭7"]);
         assert_eq!(intervals("dim7"), vec!["1", "♭3", "♭5", "♭♭7"]);
@@ -278,6 +229,18 @@ mod tests {
         assert_eq!(parse_chord_type("C＃m7b5"), ("C＃".to_string(), "m7b5".to_string()));
     }
 
+    /// ASCII・Unicode の変化記号を含むルート音
+    #[test]
+    fn test_spec_parse_ascii_and_unicode_roots() {
+        assert_eq!(parse_chord_type("C#m7"), ("C＃".to_string(), "m7".to_string()));
+        assert_eq!(parse_chord_type("Bbmaj7"), ("B♭".to_string(), "maj7".to_string()));
+        assert_eq!(parse_chord_type("F♯m7♭5"), ("F＃".to_string(), "m7b5".to_string()));
+        assert_eq!(parse_chord_type("Ebb7"), ("E♭♭".to_string(), "7".to_string()));
+        assert_eq!(parse_chord_type("Fx"), ("F＃＃".to_string(), "".to_string()));
+        assert_eq!(parse_chord_type("A7♯9"), ("A".to_string(), "7#9".to_string()));
+        assert_eq!(get_root_note("Dbm"), "D♭");
+    }
+
     /// ルート抽出の境界
     #[test]
     fn test_spec_get_root_note_edge_cases() {
@@ -289,12 +252,12 @@ mod tests {
         assert_eq!(get_root_note("D♭"), "D♭");
     }
 
-    /// 未知タイプ→メジャートライアドにフォールバック
+    /// 未知タイプはエラー（メジャートライアドにフォールバックしない）
     #[test]
-    fn test_spec_unknown_chord_type_fallback() {
-        let tones = get_chord_tones("xyz");
-        assert_eq!(tones.len(), 3);
-        let semis: Vec<i32> = tones.iter().map(|t| t.semitones).collect();
-        assert_eq!(semis, vec![0, 4, 7]);
+    fn test_spec_unknown_chord_type_error() {
+        assert_eq!(
+            get_chord_tones("xyz").unwrap_err(),
+            SidFretError::UnknownChord("xyz".to_string())
+        );
     }
 }
@@ -0,0 +1,216 @@
+//! ピッチ・MIDI ノート番号・周波数（Hz）の変換
+//! 基準ピッチ（A4 の周波数）は 440Hz 以外（442Hz, 432Hz など）も指定できる
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::core::pitch::{Pitch, MIDI_C0};
+use crate::error::SidFretError;
+
+/// A4 の MIDI ノート番号
+const A4_MIDI: f64 = 69.0;
+/// 1オクターブのセント数
+const CENTS_PER_OCTAVE: f64 = 1200.0;
+
+/// 基準ピッチ（A4 の周波数）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReferencePitch {
+    pub a4: f64,
+}
+
+impl ReferencePitch {
+    /// A4 = 440Hz
+    pub const STANDARD: Self = Self { a4: 440.0 };
+
+    /// A4 の周波数から生成（正の有限値でない場合は None）
+    pub fn new(a4: f64) -> Option<Self> {
+        (a4.is_finite() && a4 > 0.0).then_some(Self { a4 })
+    }
+
+    /// MIDI ノート番号（小数はセント単位のずれ）の周波数
+    pub fn midi_to_frequency(self, midi: f64) -> f64 {
+        self.a4 * ((midi - A4_MIDI) / 12.0).exp2()
+    }
+
+    /// 周波数に対応する MIDI ノート番号（小数、正の有限値でない場合は None）
+    pub fn frequency_to_midi(self, frequency: f64) -> Option<f64> {
+        (frequency.is_finite() && frequency > 0.0)
+            .then(|| A4_MIDI + 12.0 * (frequency / self.a4).log2())
+    }
+
+    /// ピッチの周波数（MIDI の 0〜127 の範囲外のピッチも計算できる）
+    pub fn frequency(self, pitch: Pitch) -> f64 {
+        self.midi_to_frequency((pitch.semitone() + MIDI_C0) as f64)
+    }
+
+    /// 周波数に最も近いピッチと、そのピッチからのずれ（セント）
+    pub fn nearest_pitch(self, frequency: f64) -> Option<NearestPitch> {
+        let midi = self.frequency_to_midi(frequency)?;
+        let nearest = midi.round();
+        let pitch = Pitch::from_semitone(nearest as i32 - MIDI_C0);
+        Some(NearestPitch {
+            pitch,
+            cents: (midi - nearest) * 100.0,
+            frequency: self.midi_to_frequency(nearest),
+        })
+    }
+}
+
+impl Default for ReferencePitch {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+/// 周波数に最も近いピッチ（チューナー表示用）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NearestPitch {
+    pub pitch: Pitch,
+    /// ピッチからのずれ（-50〜+50 セント、正は高い）
+    pub cents: f64,
+    /// ピッチの正しい周波数
+    pub frequency: f64,
+}
+
+/// A4 の周波数から基準ピッチを取得（不正な値はエラー）
+pub fn parse_reference(a4: f64) -> Result<ReferencePitch, SidFretError> {
+    ReferencePitch::new(a4)
+        .ok_or_else(|| SidFretError::InvalidInput(format!("invalid reference pitch {a4}")))
+}
+
+/// 2つの周波数の差（セント、to が高ければ正）
+pub fn cents_between(from: f64, to: f64) -> f64 {
+    CENTS_PER_OCTAVE * (to / from).log2()
+}
+
+/// WASM公開API: ピッチ名の周波数（例: "A4", 440 → 440.0、解釈できない場合は None）
+#[wasm_bindgen]
+pub fn pitch_to_frequency(pitch: &str, a4: f64) -> Option<f64> {
+    let reference = ReferencePitch::new(a4)?;
+    Some(reference.frequency(pitch.parse().ok()?))
+}
+
+/// WASM公開API: MIDI ノート番号の周波数
+#[wasm_bindgen]
+pub fn midi_to_frequency(midi: f64, a4: f64) -> Option<f64> {
+    Some(ReferencePitch::new(a4)?.midi_to_frequency(midi))
+}
+
+/// WASM公開API: 周波数の MIDI ノート番号（小数）
+#[wasm_bindgen]
+pub fn frequency_to_midi(frequency: f64, a4: f64) -> Option<f64> {
+    ReferencePitch::new(a4)?.frequency_to_midi(frequency)
+}
+
+/// WASM公開API: 周波数に最も近いピッチとセント差（`NearestPitch`、解釈できない場合は null）
+#[wasm_bindgen]
+pub fn nearest_pitch(frequency: f64, a4: f64) -> JsValue {
+    ReferencePitch::new(a4)
+        .and_then(|reference| reference.nearest_pitch(frequency))
+        .and_then(|nearest| serde_wasm_bindgen::to_value(&nearest).ok())
+        .unwrap_or(JsValue::NULL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pitch(name: &str) -> Pitch {
+        name.parse().unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-2, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_pitch_frequency() {
+        let standard = ReferencePitch::STANDARD;
+        assert_close(standard.frequency(pitch("A4")), 440.0);
+        assert_close(standard.frequency(pitch("E1")), 41.20);
+        assert_close(standard.frequency(pitch("B0")), 30.87);
+        assert_close(standard.frequency(pitch("G2")), 98.00);
+        assert_close(standard.frequency(pitch("C4")), 261.63);
+        // 異名同音は同じ周波数
+        assert_close(
+            standard.frequency(pitch("B♭1")),
+            standard.frequency(pitch("A＃1")),
+        );
+        // オクターブは幹音に付く（B＃3 = C4、C♭4 = B3）
+        assert_close(standard.frequency(pitch("B＃3")), 261.63);
+        assert_close(standard.frequency(pitch("C♭4")), 246.94);
+        assert_close(
+            standard.frequency(pitch("C♭♭2")),
+            standard.frequency(pitch("B♭1")),
+        );
+
+        let a442 = ReferencePitch::new(442.0).unwrap();
+        assert_close(a442.frequency(pitch("A4")), 442.0);
+        assert_close(a442.frequency(pitch("A1")), 55.25);
+        let a432 = ReferencePitch::new(432.0).unwrap();
+        assert_close(a432.frequency(pitch("E1")), 40.45);
+
+        assert_eq!(ReferencePitch::new(0.0), None);
+        assert_eq!(ReferencePitch::new(f64::NAN), None);
+        assert_eq!(ReferencePitch::default(), ReferencePitch::STANDARD);
+    }
+
+    #[test]
+    fn test_midi_frequency_round_trip() {
+        let reference = ReferencePitch::new(442.0).unwrap();
+        for midi in 0..=127 {
+            let frequency = reference.midi_to_frequency(midi as f64);
+            assert_close(reference.frequency_to_midi(frequency).unwrap(), midi as f64);
+            assert_close(reference.frequency(Pitch::from_midi(midi)), frequency);
+        }
+        assert_eq!(reference.frequency_to_midi(0.0), None);
+        assert_eq!(reference.frequency_to_midi(-41.2), None);
+    }
+
+    #[test]
+    fn test_nearest_pitch() {
+        let standard = ReferencePitch::STANDARD;
+        let nearest = standard.nearest_pitch(41.2).unwrap();
+        assert_eq!(nearest.pitch, pitch("E1"));
+        assert!(nearest.cents.abs() < 1.0);
+
+        // 10セント高い A1
+        let sharp = standard
+            .nearest_pitch(55.0 * (10.0f64 / 1200.0).exp2())
+            .unwrap();
+        assert_eq!(sharp.pitch, pitch("A1"));
+        assert_close(sharp.cents, 10.0);
+        assert_close(sharp.frequency, 55.0);
+
+        // 30セント低い D2
+        let flat_frequency = standard.frequency(pitch("D2")) * (-30.0f64 / 1200.0).exp2();
+        let flat = standard.nearest_pitch(flat_frequency).unwrap();
+        assert_eq!(flat.pitch, pitch("D2"));
+        assert_close(flat.cents, -30.0);
+
+        // 基準ピッチが違えば同じ周波数でもずれが変わる
+        let a442 = ReferencePitch::new(442.0).unwrap();
+        assert_close(
+            a442.nearest_pitch(440.0).unwrap().cents,
+            cents_between(442.0, 440.0),
+        );
+        assert_eq!(standard.nearest_pitch(0.0), None);
+    }
+
+    #[test]
+    fn test_cents_between() {
+        assert_close(cents_between(440.0, 880.0), 1200.0);
+        assert_close(cents_between(440.0, 220.0), -1200.0);
+        assert_close(cents_between(440.0, 442.0), 7.85);
+    }
+
+    #[test]
+    fn test_frequency_wasm() {
+        assert_close(pitch_to_frequency("A4", 440.0).unwrap(), 440.0);
+        assert_close(pitch_to_frequency("A4", 432.0).unwrap(), 432.0);
+        assert_eq!(pitch_to_frequency("H4", 440.0), None);
+        assert_eq!(pitch_to_frequency("A4", -1.0), None);
+        assert_close(midi_to_frequency(28.0, 440.0).unwrap(), 41.20);
+        assert_close(frequency_to_midi(41.2035, 440.0).unwrap(), 28.0);
+    }
+}
@@ -1,7 +1,12 @@
+use std::fmt;
+use std::ops::Neg;
+use std::str::FromStr;
 use wasm_bindgen::prelude::*;
 
-use crate::core::chord_type::{get_chord_tones, parse_chord_type};
-use crate::core::pitch::{absolute_semitone, pitch_map_for_root, strip_octave};
+use crate::core::chord_symbol::ChordSymbol;
+use crate::core::pitch::{
+    absolute_semitone, strip_octave, Accidental, Letter, Note, Pitch, PitchClass, MAX_OCTAVE,
+};
 
 /// 2つのピッチ間の半音距離を計算
 #[wasm_bindgen]
@@ -45,30 +50,314 @@ pub fn interval_name(semitones: i32) -> String {
     }
 }
 
-/// コードの転回形を判定
-#[wasm_bindgen]
-pub fn detect_inversion(chord: &str, bass_pitch: &str) -> i32 {
-    let (root, chord_type) = parse_chord_type(chord);
-    if root.is_empty() {
-        return -1;
-    }
+/// 度数の上限（解釈できる最低音から最高音までの音程）
+pub const MAX_NUMBER: u32 = (MAX_OCTAVE as u32 * 2 + 1) * 7;
+
+/// 長音程・完全音程の半音数（1度〜7度）
+const MAJOR_SEMITONES: [i32; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// 幹音の差 steps（0以上）の長音程・完全音程の半音数（i32 に収まらなければ None）
+fn major_semitones(steps: i32) -> Option<i32> {
+    (steps / 7)
+        .checked_mul(12)?
+        .checked_add(MAJOR_SEMITONES[(steps % 7) as usize])
+}
 
-    let frets = get_chord_tones(&chord_type);
-    let pitch_map = pitch_map_for_root(&root);
+/// 完全音程の系統か（1度・4度・5度、複音程は単音程に換算）
+fn is_perfect_degree(steps: i32) -> bool {
+    matches!(steps.rem_euclid(7), 0 | 3 | 4)
+}
+
+/// 音程の種類（重増・重減は個数で表す、例: Augmented(2) = "AA"）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Quality {
+    Diminished(u8), // 減（d, dd, ...）
+    Minor,          // 短（m）
+    Perfect,        // 完全（P）
+    Major,          // 長（M）
+    Augmented(u8),  // 増（A, AA, ...）
+}
+
+impl fmt::Display for Quality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Diminished(n) => f.write_str(&"d".repeat(n as usize)),
+            Self::Minor => f.write_str("m"),
+            Self::Perfect => f.write_str("P"),
+            Self::Major => f.write_str("M"),
+            Self::Augmented(n) => f.write_str(&"A".repeat(n as usize)),
+        }
+    }
+}
 
-    let bass_name = strip_octave(bass_pitch);
+impl FromStr for Quality {
+    type Err = ();
 
-    for (i, tone) in frets.iter().enumerate() {
-        let fret_semitone = tone.semitones as usize % 12;
-        if fret_semitone < pitch_map.len() {
-            let pitch_names = &pitch_map[fret_semitone];
-            if pitch_names.split('/').any(|p| p == bass_name) {
-                return i as i32;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let count = |c: char| -> Result<u8, ()> {
+            match u8::try_from(s.len()) {
+                Ok(n) if n > 0 && s.chars().all(|x| x == c) => Ok(n),
+                _ => Err(()),
             }
+        };
+        match s {
+            "m" => Ok(Self::Minor),
+            "P" => Ok(Self::Perfect),
+            "M" => Ok(Self::Major),
+            _ if s.starts_with('d') => count('d').map(Self::Diminished),
+            _ if s.starts_with('A') => count('A').map(Self::Augmented),
+            _ => Err(()),
+        }
+    }
+}
+
+/// 綴りを区別する音程（度数 + 種類 + 方向、例: 増4度と減5度は別の音程）
+/// steps は幹音の差（0 = 1度、7 = 8度）、semitones は半音数。下行はどちらも負
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval {
+    steps: i32,
+    semitones: i32,
+}
+
+impl Interval {
+    /// 度数（1〜）と種類から上行の音程を生成
+    /// 完全音程系に長短、長短系に完全を指定した場合、減1度、度数が MAX_NUMBER を超える場合は None
+    pub fn new(number: u32, quality: Quality) -> Option<Self> {
+        if number > MAX_NUMBER {
+            return None;
+        }
+        let steps = number.checked_sub(1)? as i32;
+        if steps == 0 && matches!(quality, Quality::Diminished(_)) {
+            return None;
+        }
+        let base = major_semitones(steps)?;
+        let diff = match (is_perfect_degree(steps), quality) {
+            (true, Quality::Perfect) | (false, Quality::Major) => 0,
+            (false, Quality::Minor) => -1,
+            (true, Quality::Diminished(n)) => -(n as i32),
+            (false, Quality::Diminished(n)) => -(n as i32) - 1,
+            (_, Quality::Augmented(n)) => n as i32,
+            _ => return None,
+        };
+        Some(Self {
+            steps,
+            semitones: base.checked_add(diff)?,
+        })
+    }
+
+    /// 2つのピッチ間の音程（to が低ければ下行、i32 に収まらない場合は None）
+    pub fn between(from: Pitch, to: Pitch) -> Option<Self> {
+        Some(Self {
+            steps: diatonic_step(to)?.checked_sub(diatonic_step(from)?)?,
+            semitones: to
+                .checked_semitone()?
+                .checked_sub(from.checked_semitone()?)?,
+        })
+    }
+
+    /// 2つの音名間の上行の単音程（from から上の最も近い to、C → C♭ のみ下行の増1度）
+    pub fn between_notes(from: Note, to: Note) -> Self {
+        let steps = (letter_index(to.letter) - letter_index(from.letter)).rem_euclid(7);
+        let semitones = to.pitch_class().semitone() as i32 - from.pitch_class().semitone() as i32;
+        // 長・完全音程との差を -6〜5 に収める
+        let base = MAJOR_SEMITONES[steps as usize];
+        Self {
+            steps,
+            semitones: base + (semitones - base + 6).rem_euclid(12) - 6,
+        }
+    }
+
+    /// 上行・下行を除いた大きさ（幹音の差・半音数）
+    fn magnitude(self) -> (i32, i32) {
+        if self.is_descending() {
+            (-self.steps, -self.semitones)
+        } else {
+            (self.steps, self.semitones)
+        }
+    }
+
+    /// 方向を指定して大きさから生成
+    fn with_direction(steps: i32, semitones: i32, descending: bool) -> Self {
+        let interval = Self { steps, semitones };
+        if descending {
+            -interval
+        } else {
+            interval
+        }
+    }
+
+    /// 下行か（1度は半音数が負なら下行）
+    pub fn is_descending(self) -> bool {
+        self.steps < 0 || (self.steps == 0 && self.semitones < 0)
+    }
+
+    /// 度数（1〜、複音程は 9度・10度…）
+    pub fn number(self) -> u32 {
+        self.magnitude().0 as u32 + 1
+    }
+
+    /// 符号付きの半音数
+    pub fn semitones(self) -> i32 {
+        self.semitones
+    }
+
+    /// 含まれるオクターブ数（M10 → 1）
+    pub fn octaves(self) -> u32 {
+        self.magnitude().0 as u32 / 7
+    }
+
+    /// オクターブを取り除いた単音程（M10 → M3、P8 → P1）
+    pub fn simple(self) -> Self {
+        let (steps, semitones) = self.magnitude();
+        let octaves = steps / 7;
+        Self::with_direction(
+            steps - octaves * 7,
+            semitones.saturating_sub(octaves.saturating_mul(12)),
+            self.is_descending(),
+        )
+    }
+
+    pub fn quality(self) -> Quality {
+        let (steps, semitones) = self.magnitude();
+        let diff = semitones.saturating_sub(major_semitones(steps).unwrap_or(i32::MAX));
+        let times = |n: i32| n.unsigned_abs().min(u8::MAX as u32) as u8;
+        match (is_perfect_degree(steps), diff) {
+            (true, 0) => Quality::Perfect,
+            (false, 0) => Quality::Major,
+            (false, -1) => Quality::Minor,
+            (_, d) if d > 0 => Quality::Augmented(times(d)),
+            (true, d) => Quality::Diminished(times(d)),
+            (false, d) => Quality::Diminished(times(d + 1)),
         }
     }
 
-    -1
+    /// 転回音程（単音程にしてから上下を入れ替える、M3 → m6、A4 → d5、P8 → P1）
+    pub fn invert(self) -> Self {
+        let (steps, semitones) = self.magnitude();
+        // 8度以上のちょうどオクターブは 8度として扱う（P8 → P1、P15 → P1）
+        let octaves = if steps > 0 && steps % 7 == 0 {
+            steps / 7 - 1
+        } else {
+            steps / 7
+        };
+        Self::with_direction(
+            7 - (steps - octaves * 7),
+            12i32.saturating_sub(semitones.saturating_sub(octaves.saturating_mul(12))),
+            self.is_descending(),
+        )
+    }
+
+    /// 音名を音程分だけ移す（例: C＃ + M3 = E＃、変化記号が ±2 を超える場合は None）
+    pub fn transpose_note(self, note: Note) -> Option<Note> {
+        let index = (letter_index(note.letter) + self.steps.rem_euclid(7)).rem_euclid(7);
+        let letter = Letter::ALL[index as usize];
+        let pitch_class = note
+            .pitch_class()
+            .transpose(self.semitones.rem_euclid(12))
+            .semitone() as i32;
+        let accidental = (pitch_class - letter.semitone() + 6).rem_euclid(12) - 6;
+        Some(Note::new(letter, Accidental::from_semitones(accidental)?))
+    }
+
+    /// ピッチを音程分だけ移す（例: E1 + -m3 = C＃1、B1 + m2 = C2）
+    /// 結果のオクターブが ±MAX_OCTAVE を超える場合も None
+    pub fn transpose_pitch(self, pitch: Pitch) -> Option<Pitch> {
+        let semitone = pitch.checked_semitone()?.checked_add(self.semitones)?;
+        let note = self.transpose_note(pitch.note)?;
+        Some(Pitch::from_note_semitone(note, semitone)).filter(|p| p.octave.abs() <= MAX_OCTAVE)
+    }
+
+    /// 音程の加算（M3 + m3 = P5、P5 + -M2 = P4、i32 に収まらない場合は None）
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Self {
+            steps: self.steps.checked_add(other.steps)?,
+            semitones: self.semitones.checked_add(other.semitones)?,
+        })
+    }
+}
+
+impl Neg for Interval {
+    type Output = Self;
+
+    /// 方向を反転
+    fn neg(self) -> Self {
+        Self {
+            steps: -self.steps,
+            semitones: -self.semitones,
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    /// "M3"、"A4"、"-m3"、"M10" の形式
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.is_descending() { "-" } else { "" };
+        write!(f, "{sign}{}{}", self.quality(), self.number())
+    }
+}
+
+impl FromStr for Interval {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (descending, rest) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let split = rest.find(|c: char| c.is_ascii_digit()).ok_or(())?;
+        let quality = rest[..split].parse()?;
+        let number = rest[split..].parse().map_err(|_| ())?;
+        let interval = Self::new(number, quality).ok_or(())?;
+        Ok(if descending { -interval } else { interval })
+    }
+}
+
+fn letter_index(letter: Letter) -> i32 {
+    Letter::ALL.iter().position(|&l| l == letter).unwrap_or(0) as i32
+}
+
+/// 幹音の通し番号（C0 = 0、D0 = 1、…、C1 = 7、オクターブは幹音に付く、i32 に収まらなければ None）
+fn diatonic_step(pitch: Pitch) -> Option<i32> {
+    pitch
+        .octave
+        .checked_mul(7)?
+        .checked_add(letter_index(pitch.note.letter))
+}
+
+/// WASM公開API: 2つのピッチ間の綴りを区別した音程名（例: "C2", "F＃2" → "A4"、下行は "-m3"）
+#[wasm_bindgen]
+pub fn spelled_interval(pitch1: &str, pitch2: &str) -> Option<String> {
+    let from: Pitch = pitch1.parse().ok()?;
+    let to: Pitch = pitch2.parse().ok()?;
+    Interval::between(from, to).map(|interval| interval.to_string())
+}
+
+/// WASM公開API: 音名またはピッチを音程分だけ移す（例: "B♭1", "M3" → "D2"、"C＃", "d3" → "E♭"）
+#[wasm_bindgen]
+pub fn transpose_by_interval(name: &str, interval: &str) -> Option<String> {
+    let interval: Interval = interval.parse().ok()?;
+    if let Ok(pitch) = name.parse::<Pitch>() {
+        return interval.transpose_pitch(pitch).map(|p| p.to_string());
+    }
+    let note: Note = name.parse().ok()?;
+    interval.transpose_note(note).map(|n| n.to_string())
+}
+
+/// コードの転回形を判定（0 = 基本形、-1 = 構成音でない）
+/// bass_pitch が空の場合は分数コードのベース音で判定する（"C/E", "" → 1）
+#[wasm_bindgen]
+pub fn detect_inversion(chord: &str, bass_pitch: &str) -> i32 {
+    let Ok(symbol) = chord.parse::<ChordSymbol>() else {
+        return -1;
+    };
+    let bass = if bass_pitch.is_empty() {
+        symbol.bass.map(Note::pitch_class)
+    } else {
+        strip_octave(bass_pitch).parse::<PitchClass>().ok()
+    };
+    // 異名同音（F＃＃ = G など）も同じ構成音として扱う
+    bass.and_then(|bass| symbol.inversion(bass))
+        .map_or(-1, |inversion| inversion as i32)
 }
 
 #[cfg(test)]
@@ -99,6 +388,191 @@ mod tests {
         assert_eq!(detect_inversion("C", "G1"), 2);
         assert_eq!(detect_inversion("Cmaj7", "B2"), 3);
         assert_eq!(detect_inversion("C", "F1"), -1);
+        // 分数コードのベース音
+        assert_eq!(detect_inversion("C/E", ""), 1);
+        assert_eq!(detect_inversion("G7/F", ""), 3);
+        assert_eq!(detect_inversion("C/D", ""), -1);
+        assert_eq!(detect_inversion("C", ""), -1);
+        assert_eq!(detect_inversion("C/G", "G1"), 2);
+    }
+
+    fn pitch(name: &str) -> Pitch {
+        name.parse().unwrap()
+    }
+
+    fn interval(name: &str) -> Interval {
+        name.parse().unwrap()
+    }
+
+    #[test]
+    fn test_interval_between_pitches() {
+        let name = |a: &str, b: &str| Interval::between(pitch(a), pitch(b)).unwrap().to_string();
+        // 同じ半音数でも綴りで区別する
+        assert_eq!(name("C2", "F＃2"), "A4");
+        assert_eq!(name("C2", "G♭2"), "d5");
+        assert_eq!(name("C2", "E♭2"), "m3");
+        assert_eq!(name("C2", "D＃2"), "A2");
+        // 重増・重減、複音程、下行
+        assert_eq!(name("C2", "F＃＃2"), "AA4");
+        assert_eq!(name("C＃2", "G♭2"), "dd5");
+        assert_eq!(name("C2", "E3"), "M10");
+        assert_eq!(name("E1", "E3"), "P15");
+        assert_eq!(name("E2", "C＃2"), "-m3");
+        assert_eq!(name("G2", "C1"), "-P12");
+        // オクターブは幹音に付く（B＃1 = C2、C♭2 = B1 の高さ）
+        assert_eq!(name("B＃1", "C＃2"), "m2");
+        assert_eq!(name("B1", "C2"), "m2");
+        assert_eq!(name("C2", "C♭2"), "-A1");
+        assert_eq!(name("C2", "C♭3"), "d8");
+    }
+
+    #[test]
+    fn test_interval_parse_and_quality() {
+        let a4 = interval("A4");
+        assert_eq!(
+            (a4.number(), a4.quality(), a4.semitones()),
+            (4, Quality::Augmented(1), 6)
+        );
+        let dd7 = interval("dd7");
+        assert_eq!(
+            (dd7.quality(), dd7.semitones()),
+            (Quality::Diminished(2), 8)
+        );
+        let m_10 = interval("-m10");
+        assert!(m_10.is_descending());
+        assert_eq!(
+            (m_10.number(), m_10.octaves(), m_10.semitones()),
+            (10, 1, -15)
+        );
+        assert_eq!(m_10.simple().to_string(), "-m3");
+        for name in ["P1", "A1", "m2", "M7", "P8", "A11", "-d5", "AA6", "ddd4"] {
+            assert_eq!(interval(name).to_string(), name);
+        }
+        for name in ["M4", "P3", "d1", "TT", "M0", "3", "Mx", "dA5"] {
+            assert!(name.parse::<Interval>().is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_interval_invert_and_add() {
+        let inverted = |name: &str| interval(name).invert().to_string();
+        assert_eq!(inverted("M3"), "m6");
+        assert_eq!(inverted("A4"), "d5");
+        assert_eq!(inverted("P1"), "P8");
+        assert_eq!(inverted("P8"), "P1");
+        assert_eq!(inverted("M10"), "m6");
+        assert_eq!(inverted("-m2"), "-M7");
+        assert_eq!(inverted("AA5"), "dd4");
+
+        let added = |a: Interval, b: Interval| a.checked_add(b).unwrap().to_string();
+        assert_eq!(added(interval("M3"), interval("m3")), "P5");
+        assert_eq!(added(interval("P5"), interval("P5")), "M9");
+        assert_eq!(added(interval("P5"), interval("-M2")), "P4");
+        assert_eq!(added(interval("M3"), interval("-P5")), "-m3");
+        assert_eq!(added(interval("A4"), interval("A4")), "A7");
+        assert_eq!(added(interval("P4"), -interval("P4")), "P1");
+        let widest = interval("P1401");
+        assert_eq!(added(widest, widest), "P2801");
+    }
+
+    #[test]
+    fn test_interval_transpose() {
+        let note = |name: &str| -> Note { name.parse().unwrap() };
+        assert_eq!(
+            interval("M3").transpose_note(note("C＃")),
+            Some(note("E＃"))
+        );
+        assert_eq!(interval("d7").transpose_note(note("C")), Some(note("B♭♭")));
+        assert_eq!(interval("-M2").transpose_note(note("C")), Some(note("B♭")));
+        assert_eq!(
+            interval("A4").transpose_note(note("F＃")),
+            Some(note("B＃"))
+        );
+        // 変化記号が ±2 を超える
+        assert_eq!(interval("AA4").transpose_note(note("F＃＃")), None);
+
+        assert_eq!(
+            interval("M3").transpose_pitch(pitch("B♭1")),
+            Some(pitch("D2"))
+        );
+        assert_eq!(
+            interval("-m3").transpose_pitch(pitch("E1")),
+            Some(pitch("C＃1"))
+        );
+        assert_eq!(
+            interval("M10").transpose_pitch(pitch("E1")),
+            Some(pitch("G＃2"))
+        );
+
+        // 結果の綴りがオクターブをまたぐ（B＃1 = C2、C♭2 = B1）
+        assert_eq!(
+            interval("A4").transpose_pitch(pitch("F＃1")),
+            Some(pitch("B＃1"))
+        );
+        assert_eq!(
+            interval("-A1").transpose_pitch(pitch("C2")),
+            Some(pitch("C♭2"))
+        );
+        assert_eq!(pitch("B＃1").semitone(), pitch("C2").semitone());
+
+        // 2音間の音程で移すと元のピッチに戻る
+        for (a, b) in [
+            ("E1", "B♭2"),
+            ("G＃2", "C1"),
+            ("B＃1", "D2"),
+            ("F♭1", "E1"),
+            ("C2", "C♭2"),
+            ("B＃1", "C＃2"),
+        ] {
+            let between = Interval::between(pitch(a), pitch(b)).unwrap();
+            assert_eq!(
+                between.transpose_pitch(pitch(a)),
+                Some(pitch(b)),
+                "{a} → {b}"
+            );
+        }
+
+        assert_eq!(
+            Interval::between_notes(note("B"), note("C")).to_string(),
+            "m2"
+        );
+        assert_eq!(
+            Interval::between_notes(note("E♭"), note("C")).to_string(),
+            "M6"
+        );
+        assert_eq!(
+            Interval::between_notes(note("C"), note("C♭")).to_string(),
+            "-A1"
+        );
+    }
+
+    #[test]
+    fn test_spelled_interval_wasm() {
+        assert_eq!(spelled_interval("C2", "G♭2").as_deref(), Some("d5"));
+        assert_eq!(spelled_interval("C2", "H2"), None);
+        assert_eq!(transpose_by_interval("B♭1", "M3").as_deref(), Some("D2"));
+        assert_eq!(transpose_by_interval("C＃", "d3").as_deref(), Some("E♭"));
+        assert_eq!(transpose_by_interval("C", "TT"), None);
+
+        // 範囲外のオクターブ・度数はパニックせずに解釈できない
+        assert_eq!(spelled_interval("C300000000", "C1"), None);
+        assert_eq!(
+            semitone_distance("C300000000", "C1"),
+            semitone_distance("H1", "C1")
+        );
+        assert_eq!(transpose_by_interval("C2", "P2000000000"), None);
+        assert_eq!(transpose_by_interval("C2", "-A2147483647"), None);
+        assert_eq!(transpose_by_interval("C2", "P1409"), None);
+        assert_eq!(transpose_by_interval("B100", "M2"), None);
+        // 範囲の両端を結ぶ音程
+        assert_eq!(
+            spelled_interval("C♭♭-100", "B＃＃100").as_deref(),
+            Some("AAAA1407")
+        );
+        assert_eq!(
+            transpose_by_interval("C-100", "P1401").as_deref(),
+            Some("C100")
+        );
     }
 
     // ===== 仕様ベーステスト =====
@@ -179,7 +653,24 @@ mod tests {
     #[test]
     fn test_spec_detect_inversion_non_member() {
         assert_eq!(detect_inversion("C", "F1"), -1);
+        // 分数コードのベース音
+        assert_eq!(detect_inversion("C/E", ""), 1);
+        assert_eq!(detect_inversion("G7/F", ""), 3);
+        assert_eq!(detect_inversion("C/D", ""), -1);
+        assert_eq!(detect_inversion("C", ""), -1);
+        assert_eq!(detect_inversion("C/G", "G1"), 2);
         assert_eq!(detect_inversion("C", "B♭2"), -1);
         assert_eq!(detect_inversion("Cm7", "F＃1"), -1);
     }
+
+    /// ダブルシャープ/ダブルフラットのベース音
+    #[test]
+    fn test_spec_detect_inversion_double_accidentals() {
+        assert_eq!(detect_inversion("G＃m", "D＃2"), 2);
+        assert_eq!(detect_inversion("E", "F＃＃1"), -1);
+        assert_eq!(detect_inversion("E♭", "A♭♭1"), 1);
+        assert_eq!(detect_inversion("D＃", "F＃＃1"), 1);
+        assert_eq!(detect_inversion("B♭m", "G♭♭2"), 2);
+        assert_eq!(detect_inversion("F＃＃", "A＃＃1"), 1);
+    }
 }
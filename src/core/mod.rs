@@ -1,9 +1,13 @@
+pub mod chord_symbol;
 pub mod chord_type;
+pub mod frequency;
 pub mod interval;
 pub mod pitch;
 pub mod scale_type;
 
+pub use chord_symbol::*;
 pub use chord_type::*;
+pub use frequency::*;
 pub use interval::*;
 pub use pitch::*;
 pub use scale_type::*;
@@ -1,6 +1,11 @@
 //! 音名・ピッチの統一基盤
-//! note_to_semitone, parse_pitch, absolute_semitone を一元化
+//! Note / PitchClass / Pitch 型と、その薄いラッパーである文字列関数（note_to_semitone, parse_pitch, absolute_semitone）
 
+use serde::de;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
 use wasm_bindgen::prelude::*;
 
 /// 12音配列（シャープ系）
@@ -18,27 +23,483 @@ pub const CHROMATIC_BOTH: [&str; 12] = [
     "C", "C＃/D♭", "D", "D＃/E♭", "E", "F", "F＃/G♭", "G", "G＃/A♭", "A", "A＃/B♭", "B",
 ];
 
-/// 音名から半音値を取得（C=0基準）
-pub fn note_to_semitone(note: &str) -> Option<i32> {
-    match note {
-        "C" | "B＃" => Some(0),
-        "C＃" | "D♭" => Some(1),
-        "D" => Some(2),
-        "D＃" | "E♭" => Some(3),
-        "E" | "F♭" => Some(4),
-        "F" | "E＃" => Some(5),
-        "F＃" | "G♭" => Some(6),
-        "G" => Some(7),
-        "G＃" | "A♭" => Some(8),
-        "A" => Some(9),
-        "A＃" | "B♭" => Some(10),
-        "B" | "C♭" => Some(11),
-        _ => None,
+/// 幹音（音名の文字）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Letter {
+    C,
+    D,
+    E,
+    F,
+    G,
+    A,
+    B,
+}
+
+impl Letter {
+    pub const ALL: [Letter; 7] = [
+        Letter::C,
+        Letter::D,
+        Letter::E,
+        Letter::F,
+        Letter::G,
+        Letter::A,
+        Letter::B,
+    ];
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'C' => Some(Self::C),
+            'D' => Some(Self::D),
+            'E' => Some(Self::E),
+            'F' => Some(Self::F),
+            'G' => Some(Self::G),
+            'A' => Some(Self::A),
+            'B' => Some(Self::B),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Self::C => 'C',
+            Self::D => 'D',
+            Self::E => 'E',
+            Self::F => 'F',
+            Self::G => 'G',
+            Self::A => 'A',
+            Self::B => 'B',
+        }
+    }
+
+    /// 幹音の半音値（C=0基準）
+    pub fn semitone(self) -> i32 {
+        match self {
+            Self::C => 0,
+            Self::D => 2,
+            Self::E => 4,
+            Self::F => 5,
+            Self::G => 7,
+            Self::A => 9,
+            Self::B => 11,
+        }
     }
 }
 
-/// ピッチ文字列（例: "C3", "E♭1"）を音名とオクターブに分割
-pub fn parse_pitch(pitch: &str) -> Option<(String, i32)> {
+/// 変化記号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Accidental {
+    DoubleFlat,  // ♭♭
+    Flat,        // ♭
+    Natural,     // 記号なし
+    Sharp,       // ＃
+    DoubleSharp, // ＃＃
+}
+
+impl Accidental {
+    /// 幹音からの半音差
+    pub fn semitones(self) -> i32 {
+        match self {
+            Self::DoubleFlat => -2,
+            Self::Flat => -1,
+            Self::Natural => 0,
+            Self::Sharp => 1,
+            Self::DoubleSharp => 2,
+        }
+    }
+
+    /// 半音差から取得（-2〜2）
+    pub fn from_semitones(semitones: i32) -> Option<Self> {
+        match semitones {
+            -2 => Some(Self::DoubleFlat),
+            -1 => Some(Self::Flat),
+            0 => Some(Self::Natural),
+            1 => Some(Self::Sharp),
+            2 => Some(Self::DoubleSharp),
+            _ => None,
+        }
+    }
+
+    /// このプロジェクトの表記（全角＃と♭）
+    pub fn to_str(self) -> &'static str {
+        self.render(AccidentalStyle::FullWidth)
+    }
+
+    /// 表記スタイルを指定して記号を取得
+    pub fn render(self, style: AccidentalStyle) -> &'static str {
+        match (style, self) {
+            (_, Self::Natural) => "",
+            (AccidentalStyle::Ascii, Self::DoubleFlat) => "bb",
+            (AccidentalStyle::Ascii, Self::Flat) => "b",
+            (AccidentalStyle::Ascii, Self::Sharp) => "#",
+            (AccidentalStyle::Ascii, Self::DoubleSharp) => "##",
+            (AccidentalStyle::Unicode, Self::DoubleFlat) => "𝄫",
+            (AccidentalStyle::Unicode, Self::Flat) => "♭",
+            (AccidentalStyle::Unicode, Self::Sharp) => "♯",
+            (AccidentalStyle::Unicode, Self::DoubleSharp) => "𝄪",
+            (AccidentalStyle::FullWidth, Self::DoubleFlat) => "♭♭",
+            (AccidentalStyle::FullWidth, Self::Flat) => "♭",
+            (AccidentalStyle::FullWidth, Self::Sharp) => "＃",
+            (AccidentalStyle::FullWidth, Self::DoubleSharp) => "＃＃",
+        }
+    }
+
+    /// 文字列の先頭から変化記号を読み取り、記号と読んだバイト数を返す
+    /// ＃ # ♯（+1）、x 𝄪（+2）、♭ b（-1）、𝄫（-2）を組み合わせた ±2 までを受け付ける
+    pub fn parse_prefix(s: &str) -> (Self, usize) {
+        let mut total: i32 = 0;
+        let mut len = 0;
+        for (i, c) in s.char_indices() {
+            let step = match c {
+                '＃' | '#' | '♯' => 1,
+                'x' | '𝄪' => 2,
+                '♭' | 'b' => -1,
+                '𝄫' => -2,
+                _ => break,
+            };
+            // シャープとフラットの混在、±2 を超える記号はそこで打ち切る
+            let next = total + step;
+            if total * step < 0 || next.abs() > 2 {
+                break;
+            }
+            total = next;
+            len = i + c.len_utf8();
+        }
+        (Self::from_semitones(total).unwrap_or(Self::Natural), len)
+    }
+}
+
+impl FromStr for Accidental {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::parse_prefix(s) {
+            (accidental, len) if len == s.len() => Ok(accidental),
+            _ => Err(()),
+        }
+    }
+}
+
+/// 変化記号の表記スタイル
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccidentalStyle {
+    Ascii,     // # b ## bb
+    Unicode,   // ♯ ♭ 𝄪 𝄫
+    FullWidth, // ＃ ♭ ＃＃ ♭♭（このプロジェクトの標準）
+}
+
+impl FromStr for AccidentalStyle {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ascii" => Ok(Self::Ascii),
+            "unicode" => Ok(Self::Unicode),
+            "full-width" | "fullwidth" => Ok(Self::FullWidth),
+            _ => Err(()),
+        }
+    }
+}
+
+impl AccidentalStyle {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::Ascii => "ascii",
+            Self::Unicode => "unicode",
+            Self::FullWidth => "full-width",
+        }
+    }
+}
+
+/// ピッチクラス（オクターブを区別しない12音、C=0基準）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PitchClass(u8);
+
+impl PitchClass {
+    /// 半音値から生成（12で割った余り）
+    pub fn new(semitone: i32) -> Self {
+        Self(semitone.rem_euclid(12) as u8)
+    }
+
+    /// 半音値（0〜11）
+    pub fn semitone(self) -> u8 {
+        self.0
+    }
+
+    pub fn transpose(self, semitones: i32) -> Self {
+        Self::new(self.0 as i32 + semitones)
+    }
+
+    /// シャープ系の表記（幹音で書ける音は幹音）
+    pub fn sharp_spelling(self) -> Note {
+        self.spell(Accidental::Sharp)
+    }
+
+    /// フラット系の表記（幹音で書ける音は幹音）
+    pub fn flat_spelling(self) -> Note {
+        self.spell(Accidental::Flat)
+    }
+
+    fn spell(self, accidental: Accidental) -> Note {
+        let naturals = Letter::ALL.map(|letter| Note::new(letter, Accidental::Natural));
+        let altered = Letter::ALL.map(|letter| Note::new(letter, accidental));
+        naturals
+            .into_iter()
+            .chain(altered)
+            .find(|note| note.pitch_class() == self)
+            .unwrap_or(naturals[0])
+    }
+}
+
+impl fmt::Display for PitchClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.sharp_spelling())
+    }
+}
+
+impl FromStr for PitchClass {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<Note>().map(|note| note.pitch_class())
+    }
+}
+
+/// 音名（幹音 + 変化記号、例: "E♭"）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Note {
+    pub letter: Letter,
+    pub accidental: Accidental,
+}
+
+impl Note {
+    pub fn new(letter: Letter, accidental: Accidental) -> Self {
+        Self { letter, accidental }
+    }
+
+    pub fn pitch_class(self) -> PitchClass {
+        PitchClass::new(self.letter.semitone() + self.accidental.semitones())
+    }
+
+    /// 半音単位で移調（フラットの音はフラット系、それ以外はシャープ系で表記）
+    pub fn transpose(self, semitones: i32) -> Self {
+        let pitch_class = self.pitch_class().transpose(semitones);
+        if self.accidental.semitones() < 0 {
+            pitch_class.flat_spelling()
+        } else {
+            pitch_class.sharp_spelling()
+        }
+    }
+}
+
+impl Note {
+    /// 表記スタイルを指定して文字列化
+    pub fn render(self, style: AccidentalStyle) -> String {
+        format!("{}{}", self.letter.to_char(), self.accidental.render(style))
+    }
+
+    /// 文字列の先頭から音名を読み取り、音名と読んだバイト数を返す（例: "C#m7" → C＃, 2）
+    pub fn parse_prefix(s: &str) -> Option<(Self, usize)> {
+        let letter = s.chars().next().and_then(Letter::from_char)?;
+        let (accidental, len) = Accidental::parse_prefix(&s[1..]);
+        Some((Self::new(letter, accidental), 1 + len))
+    }
+}
+
+impl fmt::Display for Note {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(AccidentalStyle::FullWidth))
+    }
+}
+
+impl FromStr for Note {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::parse_prefix(s) {
+            Some((note, len)) if len == s.len() => Ok(note),
+            _ => Err(()),
+        }
+    }
+}
+
+/// ピッチ（音名 + オクターブ、例: "E♭1"）
+/// オクターブは幹音に付く国際式（B＃1 は C2 と同じ高さ、C♭2 は B1 と同じ高さ）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pitch {
+    pub note: Note,
+    pub octave: i32,
+}
+
+impl Pitch {
+    pub fn new(note: Note, octave: i32) -> Self {
+        Self { note, octave }
+    }
+
+    /// 半音値から生成（シャープ系で表記、C0 = 0）
+    pub fn from_semitone(semitone: i32) -> Self {
+        Self::new(
+            PitchClass::new(semitone).sharp_spelling(),
+            semitone.div_euclid(12),
+        )
+    }
+
+    /// 絶対半音値（C0 = 0、幹音の高さに変化記号の半音差を加える、i32 に収まらなければ None）
+    pub fn checked_semitone(self) -> Option<i32> {
+        self.octave
+            .checked_mul(12)?
+            .checked_add(self.note.letter.semitone() + self.note.accidental.semitones())
+    }
+
+    /// 絶対半音値（C0 = 0、i32 に収まらない場合は端の値に丸める）
+    pub fn semitone(self) -> i32 {
+        self.octave
+            .saturating_mul(12)
+            .saturating_add(self.note.letter.semitone() + self.note.accidental.semitones())
+    }
+
+    /// オクターブをピッチクラスに付けた半音値（B＃1 = C1、C♭2 = B2、文字列関数の従来の値）
+    pub fn notated_semitone(self) -> i32 {
+        self.octave
+            .saturating_mul(12)
+            .saturating_add(self.pitch_class().semitone() as i32)
+    }
+
+    /// 音名と絶対半音値から生成（オクターブは音名の幹音に合わせる）
+    pub fn from_note_semitone(note: Note, semitone: i32) -> Self {
+        let natural = semitone - note.accidental.semitones();
+        Self::new(note, (natural - note.letter.semitone()).div_euclid(12))
+    }
+
+    pub fn pitch_class(self) -> PitchClass {
+        self.note.pitch_class()
+    }
+
+    /// 半音単位で移調（表記は Note::transpose と同じ規則）
+    pub fn transpose(self, semitones: i32) -> Self {
+        Self::from_note_semitone(self.note.transpose(semitones), self.semitone() + semitones)
+    }
+
+    /// MIDI ノート番号から生成（C-1 = 0、E1 = 28）
+    pub fn from_midi(note: u8) -> Self {
+        Self::from_semitone(note as i32 - MIDI_C0)
+    }
+
+    /// MIDI ノート番号（0〜127 の範囲外は None）
+    pub fn to_midi(self) -> Option<u8> {
+        u8::try_from(self.semitone() + MIDI_C0)
+            .ok()
+            .filter(|&note| note <= 127)
+    }
+
+    /// 同じ高さか（異名同音を区別しない）
+    pub fn is_enharmonic(self, other: Pitch) -> bool {
+        self.semitone() == other.semitone()
+    }
+}
+
+/// 文字列から解釈するオクターブの範囲（-100〜100、半音値の計算が i32 に収まるよう制限する）
+pub const MAX_OCTAVE: i32 = 100;
+
+/// C0 の MIDI ノート番号
+pub(crate) const MIDI_C0: i32 = 12;
+/// E1 の絶対半音値（C0 = 0、運指計算の E1=0 基準の原点）
+pub(crate) const E1_ABSOLUTE_SEMITONE: i32 = 16;
+
+impl Ord for Pitch {
+    /// 高さ順、同じ高さなら音名順
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.semitone()
+            .cmp(&other.semitone())
+            .then(self.note.cmp(&other.note))
+    }
+}
+
+impl PartialOrd for Pitch {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Pitch {
+    /// 表記スタイルを指定して文字列化
+    pub fn render(self, style: AccidentalStyle) -> String {
+        format!("{}{}", self.note.render(style), self.octave)
+    }
+}
+
+impl fmt::Display for Pitch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.note, self.octave)
+    }
+}
+
+impl FromStr for Pitch {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, octave) = split_octave(s).ok_or(())?;
+        let octave: i32 = octave.parse().map_err(|_| ())?;
+        if octave.abs() > MAX_OCTAVE {
+            return Err(());
+        }
+        Ok(Self::new(name.parse()?, octave))
+    }
+}
+
+/// serde では文字列（Display / FromStr の表記）として扱う
+fn serialize_display<T: fmt::Display, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+fn deserialize_from_str<'de, T: FromStr, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    s.parse()
+        .map_err(|_| de::Error::custom(format!("invalid pitch \"{s}\"")))
+}
+
+impl Serialize for PitchClass {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_display(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PitchClass {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
+impl Serialize for Note {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_display(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Note {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
+impl Serialize for Pitch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_display(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pitch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
+/// ピッチ文字列を音名部分とオクターブ部分に分割（末尾の数字と '-' をオクターブとみなす）
+fn split_octave(pitch: &str) -> Option<(&str, &str)> {
     let mut name_end = pitch.len();
     for (i, c) in pitch.char_indices().rev() {
         if c.is_ascii_digit() || c == '-' {
@@ -51,19 +512,27 @@ pub fn parse_pitch(pitch: &str) -> Option<(String, i32)> {
     if name_end == pitch.len() {
         return None;
     }
+    Some(pitch.split_at(name_end))
+}
 
-    let note_name = &pitch[..name_end];
-    let octave_str = &pitch[name_end..];
-    let octave = octave_str.parse::<i32>().ok()?;
+/// 音名から半音値を取得（C=0基準）
+pub fn note_to_semitone(note: &str) -> Option<i32> {
+    note.parse::<PitchClass>()
+        .ok()
+        .map(|pitch_class| pitch_class.semitone() as i32)
+}
 
+/// ピッチ文字列（例: "C3", "E♭1"）を音名とオクターブに分割
+/// 音名は検証しない（"H2" も ("H", 2) になる）
+pub fn parse_pitch(pitch: &str) -> Option<(String, i32)> {
+    let (note_name, octave_str) = split_octave(pitch)?;
+    let octave = octave_str.parse::<i32>().ok()?;
     Some((note_name.to_string(), octave))
 }
 
-/// ピッチの絶対半音値を計算（C0 = 0）
+/// ピッチの絶対半音値を計算（C0 = 0、オクターブはピッチクラスに付く）
 pub fn absolute_semitone(pitch: &str) -> Option<i32> {
-    let (note_name, octave) = parse_pitch(pitch)?;
-    let semitone = note_to_semitone(&note_name)?;
-    Some(octave * 12 + semitone)
+    pitch.parse::<Pitch>().ok().map(Pitch::notated_semitone)
 }
 
 /// ルート音に基づくピッチマップを計算で生成
@@ -92,6 +561,17 @@ pub fn strip_octave(pitch: &str) -> String {
     }
 }
 
+/// 音名・ピッチ文字列を表記スタイルを指定して書き直す（例: "C#2" → "C＃2"）
+/// style は "ascii" / "unicode" / "full-width"
+#[wasm_bindgen]
+pub fn render_note_name(name: &str, style: &str) -> Option<String> {
+    let style = style.parse::<AccidentalStyle>().ok()?;
+    match name.parse::<Pitch>() {
+        Ok(pitch) => Some(pitch.render(style)),
+        Err(()) => name.parse::<Note>().ok().map(|note| note.render(style)),
+    }
+}
+
 /// ピッチの異名同音比較（例: C＃2 == D♭2）
 #[wasm_bindgen]
 pub fn compare_pitch(pitch1: &str, pitch2: &str) -> bool {
@@ -101,9 +581,8 @@ pub fn compare_pitch(pitch1: &str, pitch2: &str) -> bool {
 }
 
 fn pitch_identity(pitch: &str) -> Option<(i32, i32)> {
-    let (note, octave) = parse_pitch(pitch)?;
-    let semitone = note_to_semitone(&note)?;
-    Some((octave, semitone))
+    let pitch = pitch.parse::<Pitch>().ok()?;
+    Some((pitch.octave, pitch.pitch_class().semitone() as i32))
 }
 
 #[cfg(test)]
@@ -177,6 +656,174 @@ mod tests {
         assert_eq!(fret_offset("E♭"), 11);
     }
 
+    #[test]
+    fn test_note_parse_and_display() {
+        let note: Note = "E♭".parse().unwrap();
+        assert_eq!(note, Note::new(Letter::E, Accidental::Flat));
+        assert_eq!(note.to_string(), "E♭");
+        assert_eq!(note.pitch_class(), PitchClass::new(3));
+        assert_eq!("C♭".parse::<Note>().unwrap().pitch_class().semitone(), 11);
+        assert!("H".parse::<Note>().is_err());
+        assert!("E♭♭♭".parse::<Note>().is_err());
+    }
+
+    #[test]
+    fn test_accidental_conventions() {
+        for (name, semitone) in [
+            ("C#", 1),
+            ("C♯", 1),
+            ("Db", 1),
+            ("Bb", 10),
+            ("Cx", 2),
+            ("C𝄪", 2),
+            ("C##", 2),
+            ("C＃＃", 2),
+            ("Dbb", 0),
+            ("D♭♭", 0),
+            ("D𝄫", 0),
+            ("Cb", 11),
+        ] {
+            assert_eq!(note_to_semitone(name), Some(semitone), "{name}");
+        }
+        assert_eq!(note_to_semitone("C#b"), None); // シャープとフラットの混在
+        assert_eq!(note_to_semitone("C###"), None);
+        assert_eq!(note_to_semitone("c#"), None);
+        assert_eq!(absolute_semitone("Eb1"), absolute_semitone("E♭1"));
+        assert_eq!(
+            Note::parse_prefix("C#m7"),
+            Some(("C＃".parse().unwrap(), 2))
+        );
+        assert_eq!(Note::parse_prefix("Bbm"), Some(("B♭".parse().unwrap(), 2)));
+    }
+
+    #[test]
+    fn test_accidental_rendering() {
+        let f_sharp: Note = "F#".parse().unwrap();
+        assert_eq!(f_sharp.render(AccidentalStyle::Ascii), "F#");
+        assert_eq!(f_sharp.render(AccidentalStyle::Unicode), "F♯");
+        assert_eq!(f_sharp.render(AccidentalStyle::FullWidth), "F＃");
+        assert_eq!(f_sharp.to_string(), "F＃");
+
+        let e_double_flat: Pitch = "Ebb2".parse().unwrap();
+        assert_eq!(e_double_flat.render(AccidentalStyle::Ascii), "Ebb2");
+        assert_eq!(e_double_flat.render(AccidentalStyle::Unicode), "E𝄫2");
+        assert_eq!(e_double_flat.to_string(), "E♭♭2");
+        assert_eq!(
+            "Gx".parse::<Note>().unwrap().render(AccidentalStyle::Ascii),
+            "G##"
+        );
+
+        assert_eq!(
+            render_note_name("C#2", "full-width").as_deref(),
+            Some("C＃2")
+        );
+        assert_eq!(render_note_name("B♭", "ascii").as_deref(), Some("Bb"));
+        assert_eq!(render_note_name("B♭", "braille"), None);
+        assert_eq!(render_note_name("H", "ascii"), None);
+    }
+
+    #[test]
+    fn test_pitch_class() {
+        assert_eq!(PitchClass::new(-1), PitchClass::new(11));
+        assert_eq!(PitchClass::new(10).transpose(3).semitone(), 1);
+        assert_eq!(PitchClass::new(1).to_string(), "C＃");
+        assert_eq!(PitchClass::new(1).flat_spelling().to_string(), "D♭");
+        assert_eq!(PitchClass::new(4).flat_spelling().to_string(), "E");
+        assert_eq!("D♭".parse::<PitchClass>(), "C＃".parse::<PitchClass>());
+    }
+
+    #[test]
+    fn test_pitch_parse_and_display() {
+        let pitch: Pitch = "E♭1".parse().unwrap();
+        assert_eq!(pitch.note, Note::new(Letter::E, Accidental::Flat));
+        assert_eq!(pitch.octave, 1);
+        assert_eq!(pitch.semitone(), 15);
+        assert_eq!(pitch.to_string(), "E♭1");
+        assert_eq!("G＃-1".parse::<Pitch>().unwrap().octave, -1);
+        assert!("E♭".parse::<Pitch>().is_err());
+        assert!("X3".parse::<Pitch>().is_err());
+
+        // オクターブは ±MAX_OCTAVE まで（半音値の計算があふれない）
+        assert_eq!("C-100".parse::<Pitch>().unwrap().semitone(), -1200);
+        assert!("C101".parse::<Pitch>().is_err());
+        assert!("C300000000".parse::<Pitch>().is_err());
+        assert_eq!(absolute_semitone("C300000000"), None);
+        assert!(!compare_pitch("C300000000", "C300000000"));
+        let top = Pitch::new(Note::new(Letter::C, Accidental::Natural), i32::MAX);
+        assert_eq!(top.checked_semitone(), None);
+        assert_eq!(top.semitone(), i32::MAX);
+    }
+
+    #[test]
+    fn test_pitch_transpose() {
+        let e1: Pitch = "E1".parse().unwrap();
+        assert_eq!(e1.transpose(5).to_string(), "A1");
+        assert_eq!(e1.transpose(8).to_string(), "C2");
+        assert_eq!(e1.transpose(-5).to_string(), "B0");
+        // フラットの音はフラット系で表記
+        let b_flat: Pitch = "B♭1".parse().unwrap();
+        assert_eq!(b_flat.transpose(1).to_string(), "B1");
+        assert_eq!(b_flat.transpose(3).to_string(), "D♭2");
+        assert_eq!(b_flat.transpose(-12).to_string(), "B♭0");
+    }
+
+    #[test]
+    fn test_pitch_midi() {
+        assert_eq!(Pitch::from_midi(28).to_string(), "E1");
+        assert_eq!(Pitch::from_midi(0).to_string(), "C-1");
+        assert_eq!(Pitch::from_midi(61).to_string(), "C＃4");
+        assert_eq!("E1".parse::<Pitch>().unwrap().to_midi(), Some(28));
+        assert_eq!("A4".parse::<Pitch>().unwrap().to_midi(), Some(69));
+        assert_eq!("C-2".parse::<Pitch>().unwrap().to_midi(), None);
+        assert_eq!("G＃9".parse::<Pitch>().unwrap().to_midi(), None);
+        for midi in 0..=127 {
+            assert_eq!(Pitch::from_midi(midi).to_midi(), Some(midi));
+        }
+
+        // オクターブをまたぐ異名同音（B＃3 = C4、C♭4 = B3）
+        assert_eq!("B＃3".parse::<Pitch>().unwrap().to_midi(), Some(60));
+        assert_eq!("C♭4".parse::<Pitch>().unwrap().to_midi(), Some(59));
+        assert_eq!("B＃＃3".parse::<Pitch>().unwrap().to_midi(), Some(61));
+        assert_eq!("C♭♭4".parse::<Pitch>().unwrap().to_midi(), Some(58));
+        assert_eq!("B＃3".parse::<Pitch>().unwrap().semitone(), 48);
+        // 文字列関数の絶対半音値はオクターブをピッチクラスに付ける
+        assert_eq!("B＃3".parse::<Pitch>().unwrap().notated_semitone(), 36);
+        assert_eq!(absolute_semitone("B＃3"), Some(36));
+        assert_eq!(absolute_semitone("C♭4"), Some(59));
+    }
+
+    #[test]
+    fn test_pitch_ord() {
+        let mut pitches: Vec<Pitch> = ["A1", "D♭2", "E1", "C＃2", "C2"]
+            .iter()
+            .map(|p| p.parse().unwrap())
+            .collect();
+        pitches.sort();
+        let names: Vec<String> = pitches.iter().map(Pitch::to_string).collect();
+        assert_eq!(names, ["E1", "A1", "C2", "C＃2", "D♭2"]);
+
+        let c_sharp: Pitch = "C＃2".parse().unwrap();
+        assert!(c_sharp.is_enharmonic("D♭2".parse().unwrap()));
+        assert_ne!(c_sharp, "D♭2".parse().unwrap());
+    }
+
+    #[test]
+    fn test_pitch_serde() {
+        let pitch: Pitch = "F＃2".parse().unwrap();
+        let json = serde_json::to_string(&pitch).unwrap();
+        assert_eq!(json, "\"F＃2\"");
+        assert_eq!(serde_json::from_str::<Pitch>(&json).unwrap(), pitch);
+        assert_eq!(
+            serde_json::from_str::<Note>("\"B♭\"").unwrap(),
+            Note::new(Letter::B, Accidental::Flat)
+        );
+        assert_eq!(
+            serde_json::to_string(&PitchClass::new(3)).unwrap(),
+            "\"D＃\""
+        );
+        assert!(serde_json::from_str::<Pitch>("\"H2\"").is_err());
+    }
+
     // ===== 仕様ベーステスト =====
 
     #[test]
@@ -220,6 +867,20 @@ mod tests {
         assert!(!compare_pitch("C♭2", "B1"));
     }
 
+    #[test]
+    fn test_spec_double_accidentals_round_trip() {
+        assert!(compare_pitch("F＃＃1", "G1"));
+        assert!(compare_pitch("B♭♭2", "A2"));
+        assert!(!compare_pitch("C＃＃2", "D♭♭2"));
+        assert_eq!(strip_octave("F＃＃1"), "F＃＃");
+        assert_eq!(parse_pitch("B♭♭2"), Some(("B♭♭".to_string(), 2)));
+        assert_eq!(fret_offset("F＃＃"), fret_offset("G"));
+        for name in ["F＃＃", "C＃＃", "B♭♭", "E♭♭"] {
+            let note: Note = name.parse().unwrap();
+            assert_eq!(note.to_string(), name);
+        }
+    }
+
     #[test]
     fn test_spec_absolute_semitone_range() {
         assert_eq!(absolute_semitone("C0"), Some(0));
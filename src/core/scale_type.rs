@@ -2,7 +2,7 @@
 
 use wasm_bindgen::prelude::*;
 
-use crate::core::pitch::note_to_semitone;
+use crate::core::pitch::{note_to_semitone, Accidental};
 
 /// スケール種別の半音パターンを返す
 pub fn scale_intervals(scale_type: &str) -> Option<Vec<i32>> {
@@ -34,16 +34,9 @@ fn letter_index(root: &str) -> Option<usize> {
     NATURAL_LETTERS.iter().position(|&c| c == base)
 }
 
-/// 半音差からアクシデンタル文字列を生成
+/// 半音差からアクシデンタル文字列を生成（±2 を超える場合は "?"）
 fn accidental_str(diff: i32) -> &'static str {
-    match diff {
-        -2 => "♭♭",
-        -1 => "♭",
-        0 => "",
-        1 => "＃",
-        2 => "＃＃",
-        _ => "?",
-    }
+    Accidental::from_semitones(diff).map_or("?", Accidental::to_str)
 }
 
 /// 7音スケールの各度に対応するダイアトニック文字位置
@@ -271,6 +264,24 @@ mod tests {
         assert_eq!(compute_scale_notes("F♭", ""), vec!["F♭", "G♭", "A♭", "B♭♭", "C♭", "D♭", "E♭"]);
     }
 
+    /// ダブルシャープ/ダブルフラットの音名は音名パーサーで読み戻せる
+    #[test]
+    fn test_spec_double_accidentals_round_trip() {
+        let notes = compute_scale_notes("G＃", "harm_minor");
+        assert_eq!(notes, vec!["G＃", "A＃", "B", "C＃", "D＃", "E", "F＃＃"]);
+        assert_eq!(note_to_semitone("F＃＃"), note_to_semitone("G"));
+        assert_eq!(note_to_semitone("B♭♭"), note_to_semitone("A"));
+        for st in &["", "m", "harm_minor", "melo_minor"] {
+            for root in &["B＃", "F♭", "G＃", "D＃"] {
+                let intervals = scale_intervals(st).unwrap();
+                let root_semitone = note_to_semitone(root).unwrap();
+                for (note, interval) in compute_scale_notes(root, st).iter().zip(intervals) {
+                    assert_eq!(note_to_semitone(note), Some((root_semitone + interval).rem_euclid(12)), "{root} {st}: {note}");
+                }
+            }
+        }
+    }
+
     /// スケールキーパース境界
     #[test]
     fn test_spec_parse_scale_key_edge_cases() {
@@ -0,0 +1,135 @@
+//! エラー定義
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// sid-fret のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SidFretError {
+    /// 音域外のピッチ（index = 音の位置、pitch = E1=0基準の半音値）
+    PitchOutOfRange { index: usize, pitch: i32 },
+    /// 制約を満たすポジションが存在しない（index = 音の位置）
+    InfeasibleConstraint { index: usize },
+    /// ピッチとして解釈できない入力（index = 音の位置）
+    InvalidPitch { index: usize, input: String },
+    /// 同時に鳴らす音を別々の弦で手の届く範囲に収められない（index = 和音の位置）
+    UnplayableShape { index: usize },
+    /// 未知の運指モード名
+    UnknownMode(String),
+    /// 未知のチューニング名
+    UnknownTuning(String),
+    /// コードシンボルとして解釈できない入力
+    UnknownChord(String),
+    /// JS から渡された値の形式が不正、または戻り値を変換できない
+    InvalidInput(String),
+}
+
+impl SidFretError {
+    /// JS 側で判別するためのエラーコード
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::PitchOutOfRange { .. } => "PITCH_OUT_OF_RANGE",
+            Self::InfeasibleConstraint { .. } => "INFEASIBLE_CONSTRAINT",
+            Self::InvalidPitch { .. } => "INVALID_PITCH",
+            Self::UnplayableShape { .. } => "UNPLAYABLE_SHAPE",
+            Self::UnknownMode(_) => "UNKNOWN_MODE",
+            Self::UnknownTuning(_) => "UNKNOWN_TUNING",
+            Self::UnknownChord(_) => "UNKNOWN_CHORD",
+            Self::InvalidInput(_) => "INVALID_INPUT",
+        }
+    }
+}
+
+impl fmt::Display for SidFretError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PitchOutOfRange { index, pitch } => {
+                write!(
+                    f,
+                    "note {index}: pitch {pitch} is out of range for the tuning"
+                )
+            }
+            Self::InfeasibleConstraint { index } => {
+                write!(f, "note {index}: no position satisfies the constraints")
+            }
+            Self::InvalidPitch { index, input } => {
+                write!(f, "note {index}: cannot parse pitch \"{input}\"")
+            }
+            Self::UnplayableShape { index } => {
+                write!(f, "chord {index}: no playable shape on distinct strings")
+            }
+            Self::UnknownMode(mode) => write!(f, "unknown fingering mode \"{mode}\""),
+            Self::UnknownTuning(name) => write!(f, "unknown tuning \"{name}\""),
+            Self::UnknownChord(chord) => write!(f, "unknown chord symbol \"{chord}\""),
+            Self::InvalidInput(message) => write!(f, "invalid input: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SidFretError {}
+
+/// JS には name = "SidFretError"、code・message を持つ Error として投げる
+impl From<SidFretError> for JsValue {
+    fn from(err: SidFretError) -> Self {
+        let js_err = js_sys::Error::new(&err.to_string());
+        js_err.set_name("SidFretError");
+        let _ = js_sys::Reflect::set(&js_err, &"code".into(), &err.code().into());
+        js_err.into()
+    }
+}
+
+/// 戻り値を JsValue に変換
+pub(crate) fn to_js<T: Serialize>(value: &T) -> Result<JsValue, SidFretError> {
+    serde_wasm_bindgen::to_value(value).map_err(|e| SidFretError::InvalidInput(e.to_string()))
+}
+
+/// JS から渡された値を変換
+pub(crate) fn from_js<T: DeserializeOwned>(value: JsValue) -> Result<T, SidFretError> {
+    serde_wasm_bindgen::from_value(value).map_err(|e| SidFretError::InvalidInput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_display() {
+        let err = SidFretError::InfeasibleConstraint { index: 2 };
+        assert_eq!(
+            err.to_string(),
+            "note 2: no position satisfies the constraints"
+        );
+
+        let err = SidFretError::PitchOutOfRange {
+            index: 0,
+            pitch: 99,
+        };
+        assert!(err.to_string().contains("99"));
+    }
+
+    #[test]
+    fn test_error_code() {
+        assert_eq!(
+            SidFretError::PitchOutOfRange {
+                index: 0,
+                pitch: 99
+            }
+            .code(),
+            "PITCH_OUT_OF_RANGE"
+        );
+        assert_eq!(
+            SidFretError::UnknownMode("fast".to_string()).code(),
+            "UNKNOWN_MODE"
+        );
+        assert_eq!(
+            SidFretError::UnknownTuning("banjo".to_string()).to_string(),
+            "unknown tuning \"banjo\""
+        );
+        assert_eq!(
+            SidFretError::UnknownChord("Cxyz".to_string()).code(),
+            "UNKNOWN_CHORD"
+        );
+    }
+}
@@ -1,12 +1,16 @@
+use crate::core::chord_symbol::{split_slash_bass, ChordSymbol};
+use crate::core::interval::detect_inversion;
 use crate::instrument::fretboard::get_interval;
 use crate::harmony::diatonic::get_scale_diatonic_chords_internal;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 /// 機能和声のディグリー番号を取得（harmonyUtil.ts の getFunctionalHarmony() に相当）
+/// 分数コードはベース音を除いたコードで判定する（"C/E" → Ⅰ）
 #[wasm_bindgen]
 pub fn get_functional_harmony(scale: &str, chord: &str) -> i32 {
     let chords = get_scale_diatonic_chords_internal(scale);
+    let (chord, _) = split_slash_bass(chord);
 
     if let Some(index) = chords.iter().position(|c| c == chord) {
         (index + 1) as i32
@@ -202,6 +206,7 @@ pub struct ProgressionInfo {
     cadence: String,
     is_secondary_dominant: bool,
     secondary_target: String,
+    inversion: Option<i32>,
 }
 
 #[wasm_bindgen]
@@ -235,6 +240,11 @@ impl ProgressionInfo {
     pub fn secondary_target(&self) -> String {
         self.secondary_target.clone()
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn inversion(&self) -> Option<i32> {
+        self.inversion
+    }
 }
 
 /// 複数コードの進行を分析
@@ -274,19 +284,41 @@ pub(crate) fn analyze_progression_internal(scale: &str, chords: &[String]) -> Ve
         };
 
         // セカンダリードミナント検出
+        let (base, bass) = split_slash_bass(chord);
         let (is_sec_dom, sec_target) = if degree == 0 {
-            detect_secondary_dominant(chord, &diatonic_chords)
+            detect_secondary_dominant(base, &diatonic_chords)
         } else {
             (false, String::new())
         };
 
+        // 分数コードの転回形（C/E → Ⅰ6、C/G → Ⅰ64）
+        // ベース音が構成音でなければ None とし、ルートからの音程を付ける（C/D → Ⅰ/2）
+        let inversion = match bass {
+            Some(_) => Some(detect_inversion(chord, "")).filter(|&inversion| inversion >= 0),
+            None => Some(0),
+        };
+        let roman = if degree > 0 {
+            let has_seventh = chord
+                .parse::<ChordSymbol>()
+                .is_ok_and(|symbol| symbol.seventh.is_some());
+            let figure = match (inversion, bass) {
+                (Some(inversion), _) => inversion_figure(inversion, has_seventh).to_string(),
+                (None, Some(bass)) => format!("/{}", get_interval(base, &bass.to_string())),
+                (None, None) => String::new(),
+            };
+            format!("{}{}", info.roman, figure)
+        } else {
+            info.roman
+        };
+
         results.push(ProgressionInfo {
             degree,
-            roman: info.roman,
+            roman,
             function: func_area,
             cadence,
             is_secondary_dominant: is_sec_dom,
             secondary_target: sec_target,
+            inversion,
         });
 
         prev_degree = degree;
@@ -295,6 +327,18 @@ pub(crate) fn analyze_progression_internal(scale: &str, chords: &[String]) -> Ve
     results
 }
 
+/// 転回形の数字付き低音（トライアドは 6・64、7thコードは 65・43・42）
+fn inversion_figure(inversion: i32, has_seventh: bool) -> &'static str {
+    match (has_seventh, inversion) {
+        (false, 1) => "6",
+        (false, 2) => "64",
+        (true, 1) => "65",
+        (true, 2) => "43",
+        (true, 3) => "42",
+        _ => "",
+    }
+}
+
 /// セカンダリードミナント検出
 /// 非ダイアトニックのドミナント7thコードがダイアトニックコードのV7かを判定
 fn detect_secondary_dominant(chord: &str, diatonic_chords: &[String]) -> (bool, String) {
@@ -467,6 +511,44 @@ mod tests {
         assert!(!results[0].is_secondary_dominant);
     }
 
+    /// 分数コードの転回形
+    #[test]
+    fn test_spec_progression_inversions() {
+        let chords = vec![
+            "C".to_string(),
+            "C/E".to_string(),
+            "F".to_string(),
+            "C/G".to_string(),
+            "G".to_string(),
+            "C/D".to_string(),
+        ];
+        let results = analyze_progression_internal("C", &chords);
+        let romans: Vec<&str> = results.iter().map(|r| r.roman.as_str()).collect();
+        assert_eq!(romans, ["Ⅰ", "Ⅰ6", "Ⅳ", "Ⅰ64", "Ⅴ", "Ⅰ/2"]);
+        let inversions: Vec<Option<i32>> = results.iter().map(|r| r.inversion).collect();
+        assert_eq!(inversions, [Some(0), Some(1), Some(0), Some(2), Some(0), None]);
+        assert_eq!(results[5].degree, 1);
+        assert_eq!(results[1].degree, 1);
+        assert_eq!(results[1].function, "T");
+        assert_eq!(results[4].cadence, "Half Cadence");
+
+        // 7thコードの転回形・分数コードのセカンダリードミナント
+        assert_eq!(inversion_figure(1, true), "65");
+        assert_eq!(inversion_figure(3, true), "42");
+        let chords = vec!["A7/C＃".to_string(), "Dm".to_string()];
+        let results = analyze_progression_internal("C", &chords);
+        assert!(results[0].is_secondary_dominant);
+        assert_eq!(results[0].secondary_target, "V/ii");
+        assert_eq!(results[0].inversion, Some(1));
+
+        // 構成音でないベース音はルートからの音程で示す
+        let chords = vec!["G/A♭".to_string(), "Dm/E♭".to_string()];
+        let results = analyze_progression_internal("C", &chords);
+        assert_eq!(results[0].roman, "Ⅴ/♭2");
+        assert_eq!(results[1].roman, "Ⅱ/♭2");
+        assert_eq!(results[1].inversion, None);
+    }
+
     /// 進行中のカデンツ検出
     #[test]
     fn test_spec_progression_cadence_detection() {
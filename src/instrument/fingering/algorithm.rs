@@ -1,5 +1,9 @@
+use super::optimal::{calculate_optimal, held_positions, HeldPosition};
 use super::position::{FingeringPattern, FretPosition};
+use super::reach::{HandSpan, HandWindow};
 use super::scoring::AlgorithmWeights;
+use crate::error::{to_js, SidFretError};
+use crate::instrument::tuning::Tuning;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use wasm_bindgen::prelude::*;
@@ -12,6 +16,7 @@ pub enum FingeringMode {
     StringPriority, // 弦移動優先（横移動より縦移動）
     OpenString,     // 開放弦活用
     Balanced,       // バランス型（スコアリング方式）
+    Optimal,        // 全体最適（動的計画法）
 }
 
 impl FromStr for FingeringMode {
@@ -24,6 +29,7 @@ impl FromStr for FingeringMode {
             "string" | "string-priority" => Ok(Self::StringPriority),
             "open" | "open-string" => Ok(Self::OpenString),
             "balanced" => Ok(Self::Balanced),
+            "optimal" => Ok(Self::Optimal),
             _ => Err(()),
         }
     }
@@ -37,6 +43,7 @@ impl FingeringMode {
             Self::StringPriority => "string-priority",
             Self::OpenString => "open-string",
             Self::Balanced => "balanced",
+            Self::Optimal => "optimal",
         }
     }
 
@@ -46,57 +53,140 @@ impl FingeringMode {
             Self::PositionStable => AlgorithmWeights::position_stable(),
             Self::StringPriority => AlgorithmWeights::string_priority(),
             Self::OpenString => AlgorithmWeights::open_string(),
-            Self::Balanced => AlgorithmWeights::balanced(),
+            Self::Balanced | Self::Optimal => AlgorithmWeights::balanced(),
         }
     }
 }
 
-/// 音程（半音階の絶対位置）から可能なフレットポジションを全て生成
-pub fn generate_all_positions(pitch: u8) -> Vec<FretPosition> {
-    let mut positions = Vec::new();
+/// 運指モード名を解釈（未知のモードはエラー）
+pub fn parse_mode(mode: &str) -> Result<FingeringMode, SidFretError> {
+    mode.parse()
+        .map_err(|_| SidFretError::UnknownMode(mode.to_string()))
+}
 
-    // 4弦ベースの各弦でのポジションを計算
-    // E弦（4弦）: 0-24フレット
-    if pitch <= 24 {
-        positions.push(FretPosition::new(4, pitch));
-    }
+/// チューニング名からプリセットを取得（未知の名前はエラー）
+pub fn parse_tuning(name: &str) -> Result<Tuning, SidFretError> {
+    Tuning::from_name(name).ok_or_else(|| SidFretError::UnknownTuning(name.to_string()))
+}
 
-    // A弦（3弦）: 開放=5半音
-    if (5..=29).contains(&pitch) {
-        positions.push(FretPosition::new(3, pitch - 5));
-    }
+/// 音程（E1=0基準の半音階の絶対位置）から可能なフレットポジションを全て生成
+/// 低音弦から高音弦の順で返す
+pub fn generate_all_positions(pitch: i32, tuning: &Tuning) -> Vec<FretPosition> {
+    let num_strings = tuning.strings.len();
+
+    tuning
+        .strings
+        .iter()
+        .enumerate()
+        .filter_map(|(i, string_def)| {
+            let fret = pitch - string_def.offset;
+            if (0..=tuning.max_fret).contains(&fret) {
+                Some(FretPosition::new((num_strings - i) as u8, fret as u8))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// 各音の候補ポジションを生成（候補のない音は音域外としてエラー）
+pub fn candidate_positions(
+    pitches: &[i32],
+    tuning: &Tuning,
+) -> Result<Vec<Vec<FretPosition>>, SidFretError> {
+    pitches
+        .iter()
+        .enumerate()
+        .map(|(index, &pitch)| {
+            let positions = generate_all_positions(pitch, tuning);
+            if positions.is_empty() {
+                Err(SidFretError::PitchOutOfRange { index, pitch })
+            } else {
+                Ok(positions)
+            }
+        })
+        .collect()
+}
 
-    // D弦（2弦）: 開放=10半音
-    if (10..=34).contains(&pitch) {
-        positions.push(FretPosition::new(2, pitch - 10));
+/// 各音で、手の届く範囲を保ったまま最後の音まで弾き続けられる状態
+/// 貪欲法のモードが途中で行き詰まらないよう、先の音へ続けられる候補だけを選ばせる
+struct ReachPlan<'a> {
+    hand_span: Option<&'a HandSpan>,
+    states: Vec<Vec<HeldPosition>>,
+}
+
+impl<'a> ReachPlan<'a> {
+    /// 候補のない音は音域外、手の届く経路が途切れる場合はその音で不成立としてエラー
+    fn new(
+        pitches: &[i32],
+        tuning: &Tuning,
+        hand_span: Option<&'a HandSpan>,
+    ) -> Result<Self, SidFretError> {
+        let candidates = candidate_positions(pitches, tuning)?;
+        let mut states: Vec<Vec<HeldPosition>> = Vec::with_capacity(candidates.len());
+        for (index, positions) in candidates.iter().enumerate() {
+            let prev = index.checked_sub(1).map(|prev| states[prev].as_slice());
+            let held = held_positions(prev, positions, hand_span);
+            if held.is_empty() {
+                return Err(SidFretError::InfeasibleConstraint { index });
+            }
+            states.push(held);
+        }
+
+        // 後ろから、次の音の状態へ続けられない状態を除く
+        for i in (1..states.len()).rev() {
+            let (head, tail) = states.split_at_mut(i);
+            let next = &tail[0];
+            head[i - 1].retain(|state| next.iter().any(|n| n.follows(state, hand_span)));
+        }
+        Ok(Self { hand_span, states })
     }
 
-    // G弦（1弦）: 開放=15半音
-    if (15..=39).contains(&pitch) {
-        positions.push(FretPosition::new(1, pitch - 15));
+    /// 手のポジションの範囲 window から index 番目の音として選べる候補（生成順）
+    fn candidates(&self, index: usize, window: Option<HandWindow>) -> Vec<FretPosition> {
+        self.states[index]
+            .iter()
+            .filter(|state| {
+                HeldPosition::hold(window, &state.position, self.hand_span).as_ref() == Some(state)
+            })
+            .map(|state| state.position)
+            .collect()
     }
+}
 
-    positions
+/// プリセットの重みに hand_span を設定する
+fn with_hand_span(weights: AlgorithmWeights, hand_span: Option<&HandSpan>) -> AlgorithmWeights {
+    match hand_span {
+        Some(span) => weights.with_hand_span(*span),
+        None => weights,
+    }
 }
 
 /// 最短移動アルゴリズム
-pub fn calculate_shortest_path(pitches: &[u8]) -> FingeringPattern {
+pub fn calculate_shortest_path(
+    pitches: &[i32],
+    tuning: &Tuning,
+    hand_span: Option<&HandSpan>,
+) -> Result<FingeringPattern, SidFretError> {
     if pitches.is_empty() {
-        return FingeringPattern::new(vec![], "shortest".to_string());
+        return Ok(FingeringPattern::new(vec![], "shortest".to_string()));
     }
 
     let mut selected = Vec::new();
-    let weights = AlgorithmWeights::shortest();
+    let mut window = None;
+    let weights = with_hand_span(AlgorithmWeights::shortest(), hand_span);
+    let plan = ReachPlan::new(pitches, tuning, hand_span)?;
 
     for (i, &pitch) in pitches.iter().enumerate() {
-        let candidates = generate_all_positions(pitch);
+        let candidates = plan.candidates(i, window);
 
         if i == 0 {
             // 最初の音は開放弦を優先、なければ最も低いフレット
             let best = candidates
                 .iter()
                 .min_by_key(|p| (p.fret, p.string))
-                .unwrap();
+                .ok_or(SidFretError::PitchOutOfRange { index: i, pitch })?;
+            window = HandWindow::after(window, best);
             selected.push(*best);
         } else {
             // 前の音からの移動距離が最小のものを選択
@@ -108,27 +198,35 @@ pub fn calculate_shortest_path(pitches: &[u8]) -> FingeringPattern {
                     let string_dist = (prev.string as i32 - p.string as i32).abs();
                     fret_dist + string_dist * 2 // 弦移動にペナルティ
                 })
-                .unwrap();
+                .ok_or(SidFretError::PitchOutOfRange { index: i, pitch })?;
+            window = HandWindow::after(window, best);
             selected.push(*best);
         }
     }
 
     let mut pattern = FingeringPattern::new(selected, "shortest".to_string());
     pattern.score = weights.calculate_score(&pattern);
-    pattern
+    Ok(pattern)
 }
 
 /// ポジション固定優先アルゴリズム
-pub fn calculate_position_stable(pitches: &[u8], base_position: u8) -> FingeringPattern {
+pub fn calculate_position_stable(
+    pitches: &[i32],
+    base_position: u8,
+    tuning: &Tuning,
+    hand_span: Option<&HandSpan>,
+) -> Result<FingeringPattern, SidFretError> {
     if pitches.is_empty() {
-        return FingeringPattern::new(vec![], "position-stable".to_string());
+        return Ok(FingeringPattern::new(vec![], "position-stable".to_string()));
     }
 
     let mut selected = Vec::new();
-    let weights = AlgorithmWeights::position_stable();
+    let mut window = None;
+    let weights = with_hand_span(AlgorithmWeights::position_stable(), hand_span);
+    let plan = ReachPlan::new(pitches, tuning, hand_span)?;
 
-    for &pitch in pitches {
-        let candidates = generate_all_positions(pitch);
+    for (i, &pitch) in pitches.iter().enumerate() {
+        let candidates = plan.candidates(i, window);
 
         // base_position付近のポジションを優先
         let best = candidates
@@ -138,26 +236,33 @@ pub fn calculate_position_stable(pitches: &[u8], base_position: u8) -> Fingering
                 let pos_dist = (base_position as i32 - pos as i32).abs();
                 (pos_dist, p.fret) // ポジション距離優先、次にフレット番号
             })
-            .unwrap();
+            .ok_or(SidFretError::PitchOutOfRange { index: i, pitch })?;
+        window = HandWindow::after(window, best);
         selected.push(*best);
     }
 
     let mut pattern = FingeringPattern::new(selected, "position-stable".to_string());
     pattern.score = weights.calculate_score(&pattern);
-    pattern
+    Ok(pattern)
 }
 
 /// 開放弦活用アルゴリズム
-pub fn calculate_open_string(pitches: &[u8]) -> FingeringPattern {
+pub fn calculate_open_string(
+    pitches: &[i32],
+    tuning: &Tuning,
+    hand_span: Option<&HandSpan>,
+) -> Result<FingeringPattern, SidFretError> {
     if pitches.is_empty() {
-        return FingeringPattern::new(vec![], "open-string".to_string());
+        return Ok(FingeringPattern::new(vec![], "open-string".to_string()));
     }
 
     let mut selected = Vec::new();
-    let weights = AlgorithmWeights::open_string();
+    let mut window = None;
+    let weights = with_hand_span(AlgorithmWeights::open_string(), hand_span);
+    let plan = ReachPlan::new(pitches, tuning, hand_span)?;
 
-    for &pitch in pitches {
-        let candidates = generate_all_positions(pitch);
+    for (i, &pitch) in pitches.iter().enumerate() {
+        let candidates = plan.candidates(i, window);
 
         // 開放弦を最優先、次に低いフレット
         let best = candidates
@@ -169,36 +274,45 @@ pub fn calculate_open_string(pitches: &[u8]) -> FingeringPattern {
                     (1, p.fret) // それ以外は低いフレット優先
                 }
             })
-            .unwrap();
+            .ok_or(SidFretError::PitchOutOfRange { index: i, pitch })?;
+        window = HandWindow::after(window, best);
         selected.push(*best);
     }
 
     let mut pattern = FingeringPattern::new(selected, "open-string".to_string());
     pattern.score = weights.calculate_score(&pattern);
-    pattern
+    Ok(pattern)
 }
 
 /// 弦移動優先アルゴリズム（横移動より縦移動）
-pub fn calculate_string_priority(pitches: &[u8]) -> FingeringPattern {
+pub fn calculate_string_priority(
+    pitches: &[i32],
+    tuning: &Tuning,
+    hand_span: Option<&HandSpan>,
+) -> Result<FingeringPattern, SidFretError> {
     if pitches.is_empty() {
-        return FingeringPattern::new(vec![], "string-priority".to_string());
+        return Ok(FingeringPattern::new(vec![], "string-priority".to_string()));
     }
 
     let mut selected = Vec::new();
-    let weights = AlgorithmWeights::string_priority();
+    let mut window = None;
+    let weights = with_hand_span(AlgorithmWeights::string_priority(), hand_span);
+    let plan = ReachPlan::new(pitches, tuning, hand_span)?;
 
     for (i, &pitch) in pitches.iter().enumerate() {
-        let candidates = generate_all_positions(pitch);
+        let candidates = plan.candidates(i, window);
 
         if i == 0 {
-            // 最初は中央弦（A弦かD弦）を優先
+            // 最初は中央弦を優先（4弦/5弦/6弦ともにA弦）
+            let center = (tuning.strings.len() as i32 + 2) / 2;
             let best = candidates
                 .iter()
                 .min_by_key(|p| {
-                    let string_center_dist = (p.string as i32 - 3).abs(); // 3=A弦
+                    let string_center_dist = (p.string as i32 - center).abs();
                     (string_center_dist, p.fret)
                 })
-                .unwrap();
+                .ok_or(SidFretError::PitchOutOfRange { index: i, pitch })?;
+            window = HandWindow::after(window, best);
             selected.push(*best);
         } else {
             // 弦移動を優先、フレット移動を避ける
@@ -216,59 +330,95 @@ pub fn calculate_string_priority(pitches: &[u8]) -> FingeringPattern {
                         (fret_dist * 2, 0)
                     }
                 })
-                .unwrap();
+                .ok_or(SidFretError::PitchOutOfRange { index: i, pitch })?;
+            window = HandWindow::after(window, best);
             selected.push(*best);
         }
     }
 
     let mut pattern = FingeringPattern::new(selected, "string-priority".to_string());
     pattern.score = weights.calculate_score(&pattern);
-    pattern
+    Ok(pattern)
 }
 
 /// バランス型アルゴリズム（複数要素をスコアリング）
-pub fn calculate_balanced(pitches: &[u8]) -> FingeringPattern {
+pub fn calculate_balanced(
+    pitches: &[i32],
+    tuning: &Tuning,
+    hand_span: Option<&HandSpan>,
+) -> Result<FingeringPattern, SidFretError> {
     if pitches.is_empty() {
-        return FingeringPattern::new(vec![], "balanced".to_string());
+        return Ok(FingeringPattern::new(vec![], "balanced".to_string()));
     }
 
-    // 各アルゴリズムを試してスコアを計算
-    let shortest = calculate_shortest_path(pitches);
-    let position = calculate_position_stable(pitches, 5); // 5フレット付近
-    let open = calculate_open_string(pitches);
-
-    // 最もスコアが低いものを選択
-    let weights = AlgorithmWeights::balanced();
-    vec![shortest, position, open]
-        .into_iter()
-        .min_by(|a, b| {
-            weights
-                .calculate_score(a)
-                .partial_cmp(&weights.calculate_score(b))
-                .unwrap_or(std::cmp::Ordering::Equal)
-        })
-        .map(|mut p| {
-            p.algorithm = "balanced".to_string();
-            p.score = weights.calculate_score(&p);
-            p
-        })
-        .unwrap()
+    // 各アルゴリズムを試して最もスコアが低いものを選択（同点なら先の候補）
+    let weights = with_hand_span(AlgorithmWeights::balanced(), hand_span);
+    let mut best = calculate_shortest_path(pitches, tuning, hand_span)?;
+    for candidate in [
+        calculate_position_stable(pitches, 5, tuning, hand_span)?, // 5フレット付近
+        calculate_open_string(pitches, tuning, hand_span)?,
+    ] {
+        if weights.calculate_score(&candidate) < weights.calculate_score(&best) {
+            best = candidate;
+        }
+    }
+
+    best.algorithm = "balanced".to_string();
+    best.score = weights.calculate_score(&best);
+    Ok(best)
+}
+
+/// 運指モードとチューニングから運指パターンを計算
+pub fn fingering_pattern(
+    pitches: &[i32],
+    mode: FingeringMode,
+    tuning: &Tuning,
+) -> Result<FingeringPattern, SidFretError> {
+    fingering_pattern_with_hand_span(pitches, mode, tuning, None)
+}
+
+/// 手の届く範囲を指定して運指パターンを計算（届かない候補はどのモードでも選ばない）
+pub fn fingering_pattern_with_hand_span(
+    pitches: &[i32],
+    mode: FingeringMode,
+    tuning: &Tuning,
+    hand_span: Option<&HandSpan>,
+) -> Result<FingeringPattern, SidFretError> {
+    match mode {
+        FingeringMode::Shortest => calculate_shortest_path(pitches, tuning, hand_span),
+        FingeringMode::PositionStable => calculate_position_stable(pitches, 5, tuning, hand_span),
+        FingeringMode::StringPriority => calculate_string_priority(pitches, tuning, hand_span),
+        FingeringMode::OpenString => calculate_open_string(pitches, tuning, hand_span),
+        FingeringMode::Balanced => calculate_balanced(pitches, tuning, hand_span),
+        FingeringMode::Optimal => {
+            calculate_optimal(pitches, tuning, &with_hand_span(mode.weights(), hand_span))
+        }
+    }
+}
+
+/// WASM公開API: 運指計算（4弦スタンダード）
+#[wasm_bindgen]
+pub fn calculate_fingering(pitches: Vec<u8>, mode: &str) -> Result<JsValue, JsValue> {
+    let fingering_mode = parse_mode(mode)?;
+    let pitches: Vec<i32> = pitches.into_iter().map(i32::from).collect();
+
+    let pattern = fingering_pattern(&pitches, fingering_mode, &Tuning::bass_4())?;
+    Ok(to_js(&pattern)?)
 }
 
-/// WASM公開API: 運指計算
+/// WASM公開API: チューニング指定付き運指計算
+/// pitches: E1=0基準の半音値（5弦ベースのB0は-5）
 #[wasm_bindgen]
-pub fn calculate_fingering(pitches: Vec<u8>, mode: &str) -> JsValue {
-    let fingering_mode = mode.parse().unwrap_or(FingeringMode::Balanced);
-
-    let pattern = match fingering_mode {
-        FingeringMode::Shortest => calculate_shortest_path(&pitches),
-        FingeringMode::PositionStable => calculate_position_stable(&pitches, 5),
-        FingeringMode::StringPriority => calculate_string_priority(&pitches),
-        FingeringMode::OpenString => calculate_open_string(&pitches),
-        FingeringMode::Balanced => calculate_balanced(&pitches),
-    };
-
-    serde_wasm_bindgen::to_value(&pattern).unwrap()
+pub fn calculate_fingering_with_tuning(
+    pitches: Vec<i32>,
+    mode: &str,
+    tuning_name: &str,
+) -> Result<JsValue, JsValue> {
+    let fingering_mode = parse_mode(mode)?;
+    let tuning = parse_tuning(tuning_name)?;
+
+    let pattern = fingering_pattern(&pitches, fingering_mode, &tuning)?;
+    Ok(to_js(&pattern)?)
 }
 
 #[cfg(test)]
@@ -277,26 +427,51 @@ mod tests {
 
     #[test]
     fn test_generate_all_positions() {
+        let tuning = Tuning::bass_4();
+
         // E1音（0半音）= E弦開放のみ
-        let positions = generate_all_positions(0);
+        let positions = generate_all_positions(0, &tuning);
         assert_eq!(positions.len(), 1);
         assert_eq!(positions[0].string, 4);
         assert_eq!(positions[0].fret, 0);
 
         // A1音（5半音）= E弦5フレット or A弦開放
-        let positions = generate_all_positions(5);
+        let positions = generate_all_positions(5, &tuning);
         assert_eq!(positions.len(), 2);
 
         // C2音（8半音）= E弦8フレット or A弦3フレット
-        let positions = generate_all_positions(8);
+        let positions = generate_all_positions(8, &tuning);
         assert_eq!(positions.len(), 2);
+
+        // 範囲外
+        assert!(generate_all_positions(-1, &tuning).is_empty());
+        assert!(generate_all_positions(40, &tuning).is_empty());
+    }
+
+    #[test]
+    fn test_generate_all_positions_with_tuning() {
+        // B0（-5半音）は5弦ベースの5弦開放のみ
+        let positions = generate_all_positions(-5, &Tuning::bass_5());
+        assert_eq!(positions, vec![FretPosition::new(5, 0)]);
+
+        // D1（-2半音）はドロップDの4弦開放
+        let positions = generate_all_positions(-2, &Tuning::bass_drop_d());
+        assert_eq!(positions, vec![FretPosition::new(4, 0)]);
+
+        // E1はドロップDだと4弦2フレット
+        let positions = generate_all_positions(0, &Tuning::bass_drop_d());
+        assert_eq!(positions, vec![FretPosition::new(4, 2)]);
+
+        // 6弦ベースのC弦（1弦）は最高音域をカバー
+        let positions = generate_all_positions(44, &Tuning::bass_6());
+        assert_eq!(positions, vec![FretPosition::new(1, 24)]);
     }
 
     #[test]
     fn test_calculate_shortest_path() {
         // E-F-G のシーケンス（0, 1, 3半音）
         let pitches = vec![0, 1, 3];
-        let pattern = calculate_shortest_path(&pitches);
+        let pattern = calculate_shortest_path(&pitches, &Tuning::bass_4(), None).unwrap();
 
         assert_eq!(pattern.positions.len(), 3);
         assert!(pattern.total_movement() < 10); // 最短移動のはず
@@ -306,13 +481,99 @@ mod tests {
     fn test_calculate_open_string() {
         // A音（5半音）を含むシーケンス
         let pitches = vec![5, 7, 5];
-        let pattern = calculate_open_string(&pitches);
+        let pattern = calculate_open_string(&pitches, &Tuning::bass_4(), None).unwrap();
 
         // 開放弦（A弦）を使用しているはず
         let open_count = pattern.positions.iter().filter(|p| p.fret == 0).count();
         assert!(open_count >= 1);
     }
 
+    #[test]
+    fn test_all_modes_with_5string() {
+        // B0-E1-F＃1 は5弦ベースでしか弾けない
+        let pitches = vec![-5, 0, 2];
+        let tuning = Tuning::bass_5();
+        for mode in [
+            FingeringMode::Shortest,
+            FingeringMode::PositionStable,
+            FingeringMode::StringPriority,
+            FingeringMode::OpenString,
+            FingeringMode::Balanced,
+            FingeringMode::Optimal,
+        ] {
+            let pattern = fingering_pattern(&pitches, mode, &tuning).unwrap();
+            assert_eq!(pattern.positions.len(), 3, "mode={}", mode.to_str());
+            assert_eq!(
+                pattern.positions[0],
+                FretPosition::new(5, 0),
+                "mode={}",
+                mode.to_str()
+            );
+            for (pos, &pitch) in pattern.positions.iter().zip(&pitches) {
+                assert_eq!(pos.absolute_pitch(&tuning), pitch, "mode={}", mode.to_str());
+            }
+        }
+    }
+
+    #[test]
+    fn test_drop_d_open_string() {
+        // D1-D1-A1: ドロップDなら開放弦で弾ける
+        let pattern = calculate_open_string(&[-2, -2, 5], &Tuning::bass_drop_d(), None).unwrap();
+        assert_eq!(pattern.open_string_count(), 3);
+        assert_eq!(pattern.positions[0], FretPosition::new(4, 0));
+    }
+
+    #[test]
+    fn test_all_modes_respect_hand_span() {
+        let tuning = Tuning::bass_4();
+        let modes = [
+            FingeringMode::Shortest,
+            FingeringMode::PositionStable,
+            FingeringMode::StringPriority,
+            FingeringMode::OpenString,
+            FingeringMode::Balanced,
+            FingeringMode::Optimal,
+        ];
+        // 手が小さいと F1 → C＃2 の A弦4フレットに届かない
+        let narrow = HandSpan::new(864.0, 100.0);
+        for mode in modes {
+            let pattern =
+                fingering_pattern_with_hand_span(&[1, 9], mode, &tuning, Some(&narrow)).unwrap();
+            assert_eq!(
+                pattern.positions[1],
+                FretPosition::new(4, 9),
+                "mode={}",
+                mode.to_str()
+            );
+            assert!(narrow.is_playable(&pattern), "mode={}", mode.to_str());
+            assert_eq!(
+                fingering_pattern_with_hand_span(&[1, 4], mode, &tuning, Some(&narrow))
+                    .unwrap_err(),
+                SidFretError::InfeasibleConstraint { index: 1 },
+                "mode={}",
+                mode.to_str()
+            );
+        }
+
+        // 最短移動は E弦1・2フレットから A弦5フレットへ伸ばすが、標準の手では1-5フレットに届かない
+        let span = HandSpan::long_scale();
+        let pattern = calculate_shortest_path(&[1, 2, 10], &tuning, None).unwrap();
+        assert!(!span.is_playable(&pattern));
+        let pattern = calculate_shortest_path(&[1, 2, 10], &tuning, Some(&span)).unwrap();
+        assert_eq!(pattern.positions[2], FretPosition::new(2, 0));
+        assert!(pattern.score.is_finite());
+
+        // E1, A1, F1: 5フレット付近を選ぶと E弦1フレットに届かなくなるので、先の音まで続けられる A弦開放を選ぶ
+        for mode in modes {
+            let pattern =
+                fingering_pattern_with_hand_span(&[0, 5, 1], mode, &tuning, Some(&span)).unwrap();
+            assert!(span.is_playable(&pattern), "mode={}", mode.to_str());
+            assert!(pattern.score.is_finite(), "mode={}", mode.to_str());
+        }
+        let pattern = calculate_position_stable(&[0, 5, 1], 5, &tuning, Some(&span)).unwrap();
+        assert_eq!(pattern.positions[1], FretPosition::new(3, 0));
+    }
+
     #[test]
     fn test_fingering_mode_from_str() {
         assert_eq!(
@@ -327,6 +588,47 @@ mod tests {
             "open-string".parse::<FingeringMode>(),
             Ok(FingeringMode::OpenString)
         );
+        assert_eq!(
+            "optimal".parse::<FingeringMode>(),
+            Ok(FingeringMode::Optimal)
+        );
         assert!("invalid".parse::<FingeringMode>().is_err());
     }
+
+    #[test]
+    fn test_out_of_range_pitch_is_error() {
+        // 4弦ベースの最高音（G弦24フレット = 39）を超える音はパニックせずエラー
+        for mode in [
+            FingeringMode::Shortest,
+            FingeringMode::PositionStable,
+            FingeringMode::StringPriority,
+            FingeringMode::OpenString,
+            FingeringMode::Balanced,
+            FingeringMode::Optimal,
+        ] {
+            assert_eq!(
+                fingering_pattern(&[0, 5, 40], mode, &Tuning::bass_4()).unwrap_err(),
+                SidFretError::PitchOutOfRange {
+                    index: 2,
+                    pitch: 40
+                },
+                "mode={}",
+                mode.to_str()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_mode_and_tuning() {
+        assert_eq!(parse_mode("optimal"), Ok(FingeringMode::Optimal));
+        assert_eq!(
+            parse_mode("fast"),
+            Err(SidFretError::UnknownMode("fast".to_string()))
+        );
+        assert!(parse_tuning("bass_5").is_ok());
+        assert_eq!(
+            parse_tuning("banjo").unwrap_err(),
+            SidFretError::UnknownTuning("banjo".to_string())
+        );
+    }
 }
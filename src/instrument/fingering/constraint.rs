@@ -0,0 +1,257 @@
+use super::algorithm::{generate_all_positions, parse_mode, parse_tuning};
+use super::optimal::k_best_paths;
+use super::position::{FingeringPattern, FretPosition};
+use super::scoring::AlgorithmWeights;
+use crate::error::{from_js, to_js, SidFretError};
+use crate::instrument::tuning::Tuning;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// 特定の音に対するポジション制約
+/// 同じ index に複数の制約がある場合はすべてを満たす必要がある
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoteConstraint {
+    /// 対象の音の位置（0始まり）
+    pub index: usize,
+    /// 弾く弦（1=最高音弦）
+    pub string: Option<u8>,
+    /// 弾くフレット（0=開放弦）
+    pub fret: Option<u8>,
+    /// 使ってよい最大フレット
+    pub max_fret: Option<u8>,
+    /// 開放弦を使わない
+    pub no_open: bool,
+}
+
+impl NoteConstraint {
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            ..Self::default()
+        }
+    }
+
+    pub fn on_string(mut self, string: u8) -> Self {
+        self.string = Some(string);
+        self
+    }
+
+    pub fn at_fret(mut self, fret: u8) -> Self {
+        self.fret = Some(fret);
+        self
+    }
+
+    /// 開放弦で弾く（ドローン用）
+    pub fn open(self) -> Self {
+        self.at_fret(0)
+    }
+
+    pub fn with_max_fret(mut self, max_fret: u8) -> Self {
+        self.max_fret = Some(max_fret);
+        self
+    }
+
+    pub fn without_open(mut self) -> Self {
+        self.no_open = true;
+        self
+    }
+
+    /// ポジションが制約を満たすか
+    pub fn allows(&self, pos: &FretPosition) -> bool {
+        self.string.is_none_or(|s| pos.string == s)
+            && self.fret.is_none_or(|f| pos.fret == f)
+            && self.max_fret.is_none_or(|m| pos.fret <= m)
+            && !(self.no_open && pos.fret == 0)
+    }
+}
+
+/// 制約を適用した各音の候補ポジションを生成
+pub fn constrained_candidates(
+    pitches: &[i32],
+    tuning: &Tuning,
+    constraints: &[NoteConstraint],
+) -> Result<Vec<Vec<FretPosition>>, SidFretError> {
+    if let Some(c) = constraints.iter().find(|c| c.index >= pitches.len()) {
+        return Err(SidFretError::InfeasibleConstraint { index: c.index });
+    }
+
+    pitches
+        .iter()
+        .enumerate()
+        .map(|(index, &pitch)| {
+            let positions = generate_all_positions(pitch, tuning);
+            if positions.is_empty() {
+                return Err(SidFretError::PitchOutOfRange { index, pitch });
+            }
+
+            let allowed: Vec<FretPosition> = positions
+                .into_iter()
+                .filter(|p| {
+                    constraints
+                        .iter()
+                        .filter(|c| c.index == index)
+                        .all(|c| c.allows(p))
+                })
+                .collect();
+            if allowed.is_empty() {
+                return Err(SidFretError::InfeasibleConstraint { index });
+            }
+            Ok(allowed)
+        })
+        .collect()
+}
+
+/// 制約付き最適運指（制約のない音を制約に合わせて動的計画法で最適化）
+pub fn calculate_constrained(
+    pitches: &[i32],
+    tuning: &Tuning,
+    weights: &AlgorithmWeights,
+    constraints: &[NoteConstraint],
+) -> Result<FingeringPattern, SidFretError> {
+    let candidates = constrained_candidates(pitches, tuning, constraints)?;
+
+    // 候補は揃っていても手の届く経路がない場合は最後の音で不成立とする
+    let (positions, _) = k_best_paths(&candidates, weights, None, 1)
+        .into_iter()
+        .next()
+        .ok_or(SidFretError::InfeasibleConstraint {
+            index: pitches.len().saturating_sub(1),
+        })?;
+
+    let mut pattern = FingeringPattern::new(positions, "constrained".to_string());
+    pattern.score = weights.calculate_score(&pattern);
+    Ok(pattern)
+}
+
+/// WASM公開API: 制約付き運指計算
+/// constraints: NoteConstraint の配列（例: [{ index: 0, string: 3 }, { index: 4, fret: 0 }]）
+#[wasm_bindgen]
+pub fn calculate_fingering_constrained(
+    pitches: Vec<i32>,
+    mode: &str,
+    constraints: JsValue,
+    tuning_name: &str,
+) -> Result<JsValue, JsValue> {
+    let fingering_mode = parse_mode(mode)?;
+    let tuning = parse_tuning(tuning_name)?;
+    let constraints: Vec<NoteConstraint> = from_js(constraints)?;
+
+    let pattern =
+        calculate_constrained(&pitches, &tuning, &fingering_mode.weights(), &constraints)?;
+    Ok(to_js(&pattern)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instrument::fingering::reach::HandSpan;
+
+    #[test]
+    fn test_note_constraint_allows() {
+        let pos = FretPosition::new(3, 5);
+        assert!(NoteConstraint::new(0).allows(&pos));
+        assert!(NoteConstraint::new(0).on_string(3).allows(&pos));
+        assert!(!NoteConstraint::new(0).on_string(4).allows(&pos));
+        assert!(!NoteConstraint::new(0).at_fret(0).allows(&pos));
+        assert!(!NoteConstraint::new(0).with_max_fret(4).allows(&pos));
+        assert!(!NoteConstraint::new(0)
+            .without_open()
+            .allows(&FretPosition::new(3, 0)));
+    }
+
+    #[test]
+    fn test_forced_string() {
+        let tuning = Tuning::bass_4();
+        // D2（10半音）を E弦で弾くよう指定
+        let constraints = [NoteConstraint::new(1).on_string(4)];
+        let pattern = calculate_constrained(
+            &[5, 10, 12],
+            &tuning,
+            &AlgorithmWeights::balanced(),
+            &constraints,
+        )
+        .unwrap();
+        assert_eq!(pattern.positions[1], FretPosition::new(4, 10));
+        assert_eq!(pattern.algorithm, "constrained");
+        // 周りの音も E弦10フレット付近に寄せる
+        assert!(pattern.positions[2].fret >= 7);
+    }
+
+    #[test]
+    fn test_drone_and_no_open() {
+        let tuning = Tuning::bass_4();
+        let weights = AlgorithmWeights::open_string();
+
+        // 開放弦が使える音でも no_open なら押弦
+        let constraints = [NoteConstraint::new(0).without_open()];
+        let pattern = calculate_constrained(&[5, 5], &tuning, &weights, &constraints).unwrap();
+        assert_eq!(pattern.positions[0], FretPosition::new(4, 5));
+        assert_eq!(pattern.positions[1], FretPosition::new(3, 0));
+
+        // ドローン: 開放弦を強制
+        let weights = AlgorithmWeights::position_stable();
+        let constraints = [NoteConstraint::new(1).open()];
+        let pattern =
+            calculate_constrained(&[12, 10, 12], &tuning, &weights, &constraints).unwrap();
+        assert_eq!(pattern.positions[1], FretPosition::new(2, 0));
+    }
+
+    #[test]
+    fn test_max_fret() {
+        let tuning = Tuning::bass_4();
+        let constraints: Vec<NoteConstraint> = (0..3)
+            .map(|i| NoteConstraint::new(i).with_max_fret(5))
+            .collect();
+        let pattern = calculate_constrained(
+            &[15, 17, 19],
+            &tuning,
+            &AlgorithmWeights::shortest(),
+            &constraints,
+        )
+        .unwrap();
+        assert!(pattern.positions.iter().all(|p| p.fret <= 5));
+    }
+
+    #[test]
+    fn test_infeasible_constraints() {
+        let tuning = Tuning::bass_4();
+        let weights = AlgorithmWeights::balanced();
+
+        // E1 は G弦では弾けない
+        let constraints = [NoteConstraint::new(0).on_string(1)];
+        assert_eq!(
+            calculate_constrained(&[0, 5], &tuning, &weights, &constraints).unwrap_err(),
+            SidFretError::InfeasibleConstraint { index: 0 }
+        );
+
+        // 存在しない音の位置
+        let constraints = [NoteConstraint::new(5)];
+        assert_eq!(
+            calculate_constrained(&[0], &tuning, &weights, &constraints).unwrap_err(),
+            SidFretError::InfeasibleConstraint { index: 5 }
+        );
+
+        // 音域外
+        assert_eq!(
+            calculate_constrained(&[0, 50], &tuning, &weights, &[]).unwrap_err(),
+            SidFretError::PitchOutOfRange {
+                index: 1,
+                pitch: 50
+            }
+        );
+
+        // 候補はあるが手が届かない
+        let narrow = weights.with_hand_span(HandSpan::new(864.0, 100.0));
+        let constraints = [NoteConstraint::new(1).on_string(3)];
+        assert!(calculate_constrained(&[1, 9], &tuning, &narrow, &constraints).is_err());
+    }
+
+    #[test]
+    fn test_constraint_deserialize() {
+        let json = r#"[{"index":2,"string":3},{"index":0,"no_open":true}]"#;
+        let constraints: Vec<NoteConstraint> = serde_json::from_str(json).unwrap();
+        assert_eq!(constraints[0], NoteConstraint::new(2).on_string(3));
+        assert_eq!(constraints[1], NoteConstraint::new(0).without_open());
+    }
+}
@@ -0,0 +1,267 @@
+use super::position::{FingeringPattern, POSITION_WIDTH};
+use super::rhythm::{beats_to_seconds, parse_bpm, NoteEvent};
+use crate::error::{from_js, to_js};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// フレーズの演奏時間の情報
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PhraseTiming {
+    /// テンポ（bpm）
+    pub bpm: f32,
+    /// 1小節の拍数
+    pub beats_per_bar: f32,
+    /// フレーズ全体の長さ（拍）
+    pub total_beats: f32,
+}
+
+impl PhraseTiming {
+    pub fn new(bpm: f32, beats_per_bar: f32, total_beats: f32) -> Self {
+        Self {
+            bpm,
+            beats_per_bar,
+            total_beats,
+        }
+    }
+
+    /// 同じ音価が続くフレーズ（例: 16分音符 = 0.25拍）
+    pub fn even(note_count: usize, beats_per_note: f32, bpm: f32, beats_per_bar: f32) -> Self {
+        Self::new(bpm, beats_per_bar, note_count as f32 * beats_per_note)
+    }
+
+    /// 音列の最初の発音から最後の音の終わりまで
+    pub fn from_events(events: &[NoteEvent], bpm: f32, beats_per_bar: f32) -> Self {
+        let start = events.iter().map(|e| e.onset).fold(f32::INFINITY, f32::min);
+        let end = events
+            .iter()
+            .map(|e| e.offset())
+            .fold(f32::NEG_INFINITY, f32::max);
+        let total_beats = if events.is_empty() { 0.0 } else { end - start };
+        Self::new(bpm, beats_per_bar, total_beats)
+    }
+
+    /// 演奏時間（秒）
+    pub fn seconds(&self) -> f32 {
+        beats_to_seconds(self.total_beats, self.bpm)
+    }
+
+    /// 小節数
+    pub fn bars(&self) -> f32 {
+        self.total_beats / self.beats_per_bar
+    }
+}
+
+/// 難易度の要因（生の値と難易度への寄与）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyFactor {
+    pub name: String,
+    /// 生の値（フレット数・回数・音/秒など）
+    pub value: f32,
+    /// 難易度スコアへの寄与（0.0〜1.0の合計に占める分）
+    pub contribution: f32,
+}
+
+/// 運指パターンの難易度
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyRating {
+    /// 1（易しい）〜10（難しい）
+    pub level: u8,
+    /// 0.0〜1.0 の連続値（並べ替え用）
+    pub score: f32,
+    pub factors: Vec<DifficultyFactor>,
+}
+
+/// 生の値を 0.0〜1.0 の難易度に換算する方法
+#[derive(Debug, Clone, Copy)]
+enum Normalizer {
+    /// この値を最大難易度とみなす
+    Max(f32),
+    /// 遷移あたりの割合がこの値で最大難易度とみなす
+    PerTransition(f32),
+}
+
+impl Normalizer {
+    fn normalize(self, value: f32, transitions: f32) -> f32 {
+        let normalized = match self {
+            Self::Max(max) => value / max,
+            Self::PerTransition(max) => value / transitions / max,
+        };
+        normalized.min(1.0)
+    }
+}
+
+/// 要因名、重み、換算方法
+const FACTORS: [(&str, f32, Normalizer); 6] = [
+    ("largest_shift", 1.5, Normalizer::Max(12.0)),
+    // 1ポジション内の最大幅（人差し指から小指まで）
+    ("stretch", 1.0, Normalizer::Max((POSITION_WIDTH - 1) as f32)),
+    ("string_skips", 1.0, Normalizer::PerTransition(0.5)),
+    ("highest_fret", 0.5, Normalizer::Max(20.0)),
+    ("notes_per_second", 2.5, Normalizer::Max(8.0)),
+    ("position_changes_per_bar", 1.5, Normalizer::Max(4.0)),
+];
+
+/// 運指パターンの難易度を評価する
+pub fn rate_difficulty(pattern: &FingeringPattern, timing: &PhraseTiming) -> DifficultyRating {
+    let transitions = pattern.positions.len().saturating_sub(1).max(1) as f32;
+    let seconds = timing.seconds();
+    let bars = timing.bars();
+
+    let values = [
+        pattern.largest_shift() as f32,
+        pattern.max_stretch() as f32,
+        pattern.string_skips() as f32,
+        pattern.highest_fret() as f32,
+        if seconds > 0.0 {
+            pattern.positions.len() as f32 / seconds
+        } else {
+            0.0
+        },
+        if bars > 0.0 {
+            pattern.position_changes() as f32 / bars
+        } else {
+            0.0
+        },
+    ];
+
+    let total_weight: f32 = FACTORS.iter().map(|(_, weight, _)| weight).sum();
+    let factors: Vec<DifficultyFactor> = FACTORS
+        .iter()
+        .zip(values)
+        .map(|(&(name, weight, normalizer), value)| DifficultyFactor {
+            name: name.to_string(),
+            value,
+            contribution: normalizer.normalize(value, transitions) * weight / total_weight,
+        })
+        .collect();
+
+    let score = factors.iter().map(|f| f.contribution).sum::<f32>();
+    DifficultyRating {
+        level: 1 + (score * 9.0).round() as u8,
+        score,
+        factors,
+    }
+}
+
+/// WASM公開API: 運指パターンの難易度
+/// pattern: calculate_fingering などの戻り値、beats_per_note: 1音あたりの拍数
+#[wasm_bindgen]
+pub fn get_fingering_difficulty(
+    pattern: JsValue,
+    bpm: f32,
+    beats_per_note: f32,
+    beats_per_bar: f32,
+) -> Result<JsValue, JsValue> {
+    let bpm = parse_bpm(bpm)?;
+    let pattern: FingeringPattern = from_js(pattern)?;
+    let timing = PhraseTiming::even(pattern.positions.len(), beats_per_note, bpm, beats_per_bar);
+
+    let rating = rate_difficulty(&pattern, &timing);
+    Ok(to_js(&rating)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instrument::fingering::position::FretPosition;
+
+    fn pattern(positions: &[(u8, u8)]) -> FingeringPattern {
+        FingeringPattern::new(
+            positions
+                .iter()
+                .map(|&(string, fret)| FretPosition::new(string, fret))
+                .collect(),
+            "test".to_string(),
+        )
+    }
+
+    fn factor(rating: &DifficultyRating, name: &str) -> f32 {
+        rating
+            .factors
+            .iter()
+            .find(|f| f.name == name)
+            .map(|f| f.value)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_phrase_timing() {
+        let timing = PhraseTiming::even(8, 0.5, 120.0, 4.0);
+        assert_eq!(timing.total_beats, 4.0);
+        assert_eq!(timing.seconds(), 2.0);
+        assert_eq!(timing.bars(), 1.0);
+
+        let events = NoteEvent::sequence(&[(0, 1.0), (5, 2.0)]);
+        assert_eq!(
+            PhraseTiming::from_events(&events, 60.0, 4.0).total_beats,
+            3.0
+        );
+        assert_eq!(PhraseTiming::from_events(&[], 60.0, 4.0).total_beats, 0.0);
+    }
+
+    #[test]
+    fn test_easy_and_hard_phrases() {
+        // 開放弦と1ポジションの全音符
+        let easy = pattern(&[(4, 0), (4, 3), (3, 0), (3, 2)]);
+        let easy = rate_difficulty(&easy, &PhraseTiming::even(4, 4.0, 80.0, 4.0));
+
+        // ハイポジションへの跳躍と弦飛ばしを含む16分音符
+        let hard = pattern(&[
+            (4, 1),
+            (2, 4),
+            (4, 12),
+            (1, 17),
+            (3, 5),
+            (1, 15),
+            (4, 3),
+            (2, 14),
+        ]);
+        let hard = rate_difficulty(&hard, &PhraseTiming::even(8, 0.25, 160.0, 4.0));
+
+        assert!((1..=3).contains(&easy.level));
+        assert!(hard.level >= 7);
+        assert!(easy.score < hard.score);
+        assert!((1..=10).contains(&hard.level));
+
+        assert_eq!(factor(&hard, "highest_fret"), 17.0);
+        assert_eq!(factor(&hard, "string_skips"), 7.0);
+        assert!(factor(&hard, "notes_per_second") > 10.0);
+    }
+
+    #[test]
+    fn test_tempo_raises_difficulty() {
+        let phrase = pattern(&[(4, 1), (4, 3), (3, 5), (3, 7), (2, 9)]);
+        let slow = rate_difficulty(&phrase, &PhraseTiming::even(5, 1.0, 60.0, 4.0));
+        let fast = rate_difficulty(&phrase, &PhraseTiming::even(5, 0.25, 180.0, 4.0));
+        assert!(fast.score > slow.score);
+        assert!(
+            factor(&fast, "position_changes_per_bar") > factor(&slow, "position_changes_per_bar")
+        );
+    }
+
+    #[test]
+    fn test_stretch_reaches_full_difficulty() {
+        // 1ポジション内で人差し指から小指まで広げた形が最大
+        let stretch = pattern(&[(4, 1), (3, 4)]);
+        let rating = rate_difficulty(&stretch, &PhraseTiming::even(2, 1.0, 100.0, 4.0));
+        let contribution = rating
+            .factors
+            .iter()
+            .find(|f| f.name == "stretch")
+            .map(|f| f.contribution)
+            .unwrap();
+        let total_weight: f32 = FACTORS.iter().map(|(_, weight, _)| weight).sum();
+        assert_eq!(factor(&rating, "stretch"), 3.0);
+        assert!((contribution - 1.0 / total_weight).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_contributions_sum_to_score() {
+        let phrase = pattern(&[(4, 5), (3, 7), (1, 9)]);
+        let rating = rate_difficulty(&phrase, &PhraseTiming::even(3, 1.0, 100.0, 4.0));
+        let sum: f32 = rating.factors.iter().map(|f| f.contribution).sum();
+        assert!((sum - rating.score).abs() < 1e-6);
+        assert!(rating.factors.iter().all(|f| f.contribution >= 0.0));
+        assert_eq!(rating.factors.len(), 6);
+    }
+}
@@ -0,0 +1,225 @@
+use super::position::{FingeringPattern, FretPosition};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// 左手の指使いシステム
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FingerSystem {
+    OneFingerPerFret, // 1フレット1指（4フレットを1-2-3-4指で担当）
+    Simandl,          // シマンドル式（3フレットを1-2-4指で担当）
+}
+
+impl FromStr for FingerSystem {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "one-finger-per-fret" | "ofpf" => Ok(Self::OneFingerPerFret),
+            "simandl" | "1-2-4" => Ok(Self::Simandl),
+            _ => Err(()),
+        }
+    }
+}
+
+impl FingerSystem {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::OneFingerPerFret => "one-finger-per-fret",
+            Self::Simandl => "simandl",
+        }
+    }
+
+    /// 人差し指のフレットからの相対位置ごとの指番号
+    fn fingers(&self) -> &'static [u8] {
+        match self {
+            Self::OneFingerPerFret => &[1, 2, 3, 4],
+            Self::Simandl => &[1, 2, 4],
+        }
+    }
+
+    /// 手を動かさずに押さえられるフレット数
+    fn span(&self) -> i32 {
+        self.fingers().len() as i32
+    }
+}
+
+/// 人差し指のフレット（anchor）から見た指番号
+/// ストレッチ（範囲外1フレット）は人差し指か小指を伸ばして対応
+fn finger_at(system: FingerSystem, anchor: i32, fret: i32, allow_stretch: bool) -> Option<u8> {
+    let offset = fret - anchor;
+    let fingers = system.fingers();
+    if (0..system.span()).contains(&offset) {
+        Some(fingers[offset as usize])
+    } else if allow_stretch && offset == -1 && anchor > 1 {
+        Some(1)
+    } else if allow_stretch && offset == system.span() {
+        Some(4)
+    } else {
+        None
+    }
+}
+
+/// anchor から連続して押さえられる後続の音数
+fn covered_run(system: FingerSystem, anchor: i32, frets: &[i32]) -> usize {
+    frets
+        .iter()
+        .take_while(|&&fret| finger_at(system, anchor, fret, false).is_some())
+        .count()
+}
+
+/// 運指パターンの各音に左手の指番号を割り当てる
+/// 開放弦は指を使わないので None、それ以外の音には必ず指番号が入る
+pub fn assign_fingers(pattern: &FingeringPattern, system: FingerSystem) -> FingeringPattern {
+    let mut anchor: Option<i32> = None;
+    let mut positions: Vec<FretPosition> = Vec::with_capacity(pattern.positions.len());
+
+    for (i, pos) in pattern.positions.iter().enumerate() {
+        if pos.fret == 0 {
+            // 開放弦の間は手のポジションを維持する
+            positions.push(FretPosition {
+                finger: None,
+                ..*pos
+            });
+            continue;
+        }
+
+        let fret = pos.fret as i32;
+        // 以降の押弦音（開放弦を除く）
+        let upcoming: Vec<i32> = pattern.positions[i..]
+            .iter()
+            .filter(|p| p.fret != 0)
+            .map(|p| p.fret as i32)
+            .collect();
+
+        let current = anchor.and_then(|a| {
+            // 次の押弦音が元のポジションに戻る場合のみストレッチを許可
+            let returns = upcoming
+                .get(1)
+                .is_none_or(|&next| finger_at(system, a, next, false).is_some());
+            finger_at(system, a, fret, returns)
+        });
+
+        let finger = match current {
+            Some(finger) => finger,
+            None => {
+                // ポジション移動: 後続の音を最も多く押さえられる位置へ
+                // 同数なら移動距離が最小の位置を選ぶ
+                let new_anchor = (fret - system.span() + 1..=fret)
+                    .filter(|&a| a >= 1)
+                    .max_by_key(|&a| {
+                        let shift = anchor.map_or(fret - a, |prev| (prev - a).abs());
+                        (covered_run(system, a, &upcoming), -shift)
+                    })
+                    .unwrap_or(fret);
+                anchor = Some(new_anchor);
+                finger_at(system, new_anchor, fret, false).unwrap_or(1)
+            }
+        };
+
+        positions.push(pos.with_finger(finger));
+    }
+
+    FingeringPattern::new(positions, pattern.algorithm.clone()).with_score(pattern.score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern_on_string(string: u8, frets: &[u8]) -> FingeringPattern {
+        FingeringPattern::new(
+            frets
+                .iter()
+                .map(|&f| FretPosition::new(string, f))
+                .collect(),
+            "test".to_string(),
+        )
+    }
+
+    fn fingers(pattern: &FingeringPattern) -> Vec<Option<u8>> {
+        pattern.positions.iter().map(|p| p.finger).collect()
+    }
+
+    #[test]
+    fn test_one_finger_per_fret() {
+        let pattern = pattern_on_string(4, &[1, 2, 3, 4]);
+        let result = assign_fingers(&pattern, FingerSystem::OneFingerPerFret);
+        assert_eq!(fingers(&result), vec![Some(1), Some(2), Some(3), Some(4)]);
+    }
+
+    #[test]
+    fn test_simandl() {
+        let pattern = pattern_on_string(4, &[1, 2, 3]);
+        let result = assign_fingers(&pattern, FingerSystem::Simandl);
+        assert_eq!(fingers(&result), vec![Some(1), Some(2), Some(4)]);
+    }
+
+    #[test]
+    fn test_position_shift() {
+        // 1-4フレットの後に5-8フレット → 5フレットへシフト
+        let pattern = pattern_on_string(3, &[1, 2, 3, 4, 5, 6, 7, 8]);
+        let result = assign_fingers(&pattern, FingerSystem::OneFingerPerFret);
+        assert_eq!(
+            fingers(&result),
+            [1, 2, 3, 4, 1, 2, 3, 4].map(Some).to_vec()
+        );
+
+        // 下降フレーズは小指から入る
+        let pattern = pattern_on_string(3, &[7, 6, 5]);
+        let result = assign_fingers(&pattern, FingerSystem::Simandl);
+        assert_eq!(fingers(&result), vec![Some(4), Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn test_open_strings_and_string_crossing() {
+        let pattern = FingeringPattern::new(
+            vec![
+                FretPosition::new(4, 0),
+                FretPosition::new(4, 3),
+                FretPosition::new(3, 0),
+                FretPosition::new(3, 2),
+                FretPosition::new(2, 3),
+            ],
+            "test".to_string(),
+        );
+        let result = assign_fingers(&pattern, FingerSystem::OneFingerPerFret);
+        assert_eq!(
+            fingers(&result),
+            vec![None, Some(2), None, Some(1), Some(2)]
+        );
+        assert_eq!(result.algorithm, "test");
+    }
+
+    #[test]
+    fn test_stretch() {
+        // 5フレットだけ範囲外で、次の音が元のポジションに戻る → 小指ストレッチ
+        let pattern = pattern_on_string(4, &[1, 3, 5, 3]);
+        let result = assign_fingers(&pattern, FingerSystem::OneFingerPerFret);
+        assert_eq!(fingers(&result), vec![Some(1), Some(3), Some(4), Some(3)]);
+
+        // 戻らない場合は移動距離が最小になる4フレットへシフト
+        let pattern = pattern_on_string(4, &[1, 3, 5, 7]);
+        let result = assign_fingers(&pattern, FingerSystem::OneFingerPerFret);
+        assert_eq!(result.positions[2].finger, Some(2));
+        assert_eq!(result.positions[3].finger, Some(4));
+    }
+
+    #[test]
+    fn test_every_fretted_note_gets_a_finger() {
+        let pattern = pattern_on_string(2, &[12, 0, 3, 15, 1, 24, 7, 0, 9]);
+        for system in [FingerSystem::OneFingerPerFret, FingerSystem::Simandl] {
+            let result = assign_fingers(&pattern, system);
+            for pos in &result.positions {
+                assert_eq!(pos.finger.is_some(), pos.fret != 0);
+                assert!(pos.finger.is_none_or(|f| (1..=4).contains(&f)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_finger_system_from_str() {
+        assert_eq!("simandl".parse(), Ok(FingerSystem::Simandl));
+        assert_eq!("ofpf".parse(), Ok(FingerSystem::OneFingerPerFret));
+        assert!("invalid".parse::<FingerSystem>().is_err());
+    }
+}
@@ -0,0 +1,360 @@
+use super::algorithm::{generate_all_positions, parse_mode, parse_tuning};
+use super::optimal::k_best_by;
+use super::position::FretPosition;
+use super::reach::HandSpan;
+use super::scoring::AlgorithmWeights;
+use crate::error::{from_js, to_js, SidFretError};
+use crate::instrument::fretboard::chord_positions;
+use crate::instrument::tuning::Tuning;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// 1つの形で押さえられる最大フレット幅（4フレットを1指ずつ）
+const MAX_FRET_SPAN: u8 = 3;
+/// 同時に押弦できる最大音数（左手の指の数）
+const MAX_FRETTED_NOTES: usize = 4;
+
+/// 同時に鳴らす音のポジション（ダブルストップ・パワーコード・コードボイシング）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionGroup {
+    pub positions: Vec<FretPosition>,
+}
+
+impl PositionGroup {
+    pub fn new(positions: Vec<FretPosition>) -> Self {
+        Self { positions }
+    }
+
+    /// 全ての音が別々の弦にあるか
+    pub fn has_distinct_strings(&self) -> bool {
+        self.positions
+            .iter()
+            .enumerate()
+            .all(|(i, p)| self.positions[..i].iter().all(|q| q.string != p.string))
+    }
+
+    fn fretted(&self) -> impl Iterator<Item = &FretPosition> {
+        self.positions.iter().filter(|p| p.fret != 0)
+    }
+
+    /// 押弦音のフレット幅（開放弦は除く）
+    pub fn fret_span(&self) -> u8 {
+        let min = self.fretted().map(|p| p.fret).min();
+        let max = self.fretted().map(|p| p.fret).max();
+        match (min, max) {
+            (Some(min), Some(max)) => max - min,
+            _ => 0,
+        }
+    }
+
+    /// 手の位置の基準となる音（最も低いフレットの押弦音）
+    /// 全て開放弦なら None
+    pub fn hand_position(&self) -> Option<FretPosition> {
+        self.fretted().min_by_key(|p| p.fret).copied()
+    }
+
+    /// 遷移コストの基準にする音（押弦音があれば hand_position、なければ最初の音）
+    /// 単音のグループではその音自身になる
+    pub fn reference_position(&self) -> Option<FretPosition> {
+        self.hand_position().or(self.positions.first().copied())
+    }
+
+    /// 手の形に収まるか
+    /// hand_span 指定時は実寸、それ以外は4フレット以内で判定する
+    pub fn fits_hand(&self, hand_span: Option<&HandSpan>) -> bool {
+        if self.fretted().count() > MAX_FRETTED_NOTES {
+            return false;
+        }
+        match (hand_span, self.hand_position()) {
+            (Some(span), Some(low)) => {
+                let high = low.fret + self.fret_span();
+                span.span_between(low.fret, high) <= span.max_span
+            }
+            _ => self.fret_span() <= MAX_FRET_SPAN,
+        }
+    }
+
+    /// 別々の弦で手に収まる（実際に弾ける）形か
+    pub fn is_playable(&self, hand_span: Option<&HandSpan>) -> bool {
+        self.has_distinct_strings() && self.fits_hand(hand_span)
+    }
+}
+
+/// ポジショングループの運指パターン
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupPattern {
+    pub groups: Vec<PositionGroup>,
+    pub score: f32,
+    pub algorithm: String,
+}
+
+/// 各音の候補ポジションから、弾ける組み合わせを全て列挙する
+fn playable_groups(
+    candidates: &[Vec<FretPosition>],
+    hand_span: Option<&HandSpan>,
+) -> Vec<PositionGroup> {
+    fn search(
+        candidates: &[Vec<FretPosition>],
+        current: &mut Vec<FretPosition>,
+        hand_span: Option<&HandSpan>,
+        result: &mut Vec<PositionGroup>,
+    ) {
+        if current.len() == candidates.len() {
+            result.push(PositionGroup::new(current.clone()));
+            return;
+        }
+        for p in &candidates[current.len()] {
+            if current.iter().any(|q| q.string == p.string) {
+                continue;
+            }
+            current.push(*p);
+            // 途中で手に収まらなくなった組み合わせは打ち切る
+            if PositionGroup::new(current.clone()).fits_hand(hand_span) {
+                search(candidates, current, hand_span, result);
+            }
+            current.pop();
+        }
+    }
+
+    let mut result = Vec::new();
+    search(candidates, &mut Vec::new(), hand_span, &mut result);
+    result
+}
+
+/// 同時に鳴らす音（E1=0基準）の弾ける形を全て列挙
+pub fn generate_groups(
+    pitches: &[i32],
+    tuning: &Tuning,
+    hand_span: Option<&HandSpan>,
+) -> Vec<PositionGroup> {
+    let candidates: Vec<Vec<FretPosition>> = pitches
+        .iter()
+        .map(|&pitch| generate_all_positions(pitch, tuning))
+        .collect();
+    playable_groups(&candidates, hand_span)
+}
+
+/// 和音列の最適運指（各ステップの形を動的計画法で選ぶ）
+/// steps[i] は i 番目に同時に鳴らす音の配列（1音なら単音）
+pub fn calculate_polyphonic(
+    steps: &[Vec<i32>],
+    tuning: &Tuning,
+    weights: &AlgorithmWeights,
+) -> Result<GroupPattern, SidFretError> {
+    let hand_span = weights.hand_span.as_ref();
+    let mut candidates = Vec::with_capacity(steps.len());
+    for (index, pitches) in steps.iter().enumerate() {
+        if let Some(&pitch) = pitches
+            .iter()
+            .find(|&&pitch| generate_all_positions(pitch, tuning).is_empty())
+        {
+            return Err(SidFretError::PitchOutOfRange { index, pitch });
+        }
+        let groups = generate_groups(pitches, tuning, hand_span);
+        if groups.is_empty() {
+            return Err(SidFretError::UnplayableShape { index });
+        }
+        candidates.push(groups);
+    }
+
+    let (groups, score) = k_best_by(
+        &candidates,
+        1,
+        |_, group| weights.group_cost(group),
+        |_, prev, curr| weights.group_transition_cost(prev, curr),
+    )
+    .into_iter()
+    .next()
+    .ok_or(SidFretError::UnplayableShape {
+        index: steps.len().saturating_sub(1),
+    })?;
+
+    Ok(GroupPattern {
+        groups,
+        score,
+        algorithm: "polyphonic".to_string(),
+    })
+}
+
+/// コードの構成音を1音ずつ別々の弦に置いた弾ける形（コストの低い順）
+/// 構成音が弦の数より多いコードは空
+pub fn chord_shapes(
+    chord: &str,
+    tuning: &Tuning,
+    weights: &AlgorithmWeights,
+) -> Vec<PositionGroup> {
+    // 構成音（インターバル）ごとの候補
+    let mut candidates: Vec<(String, Vec<FretPosition>)> = Vec::new();
+    for pos in chord_positions(chord, tuning) {
+        let fret_pos = FretPosition::new(pos.string() as u8, pos.fret() as u8);
+        match candidates
+            .iter_mut()
+            .find(|(interval, _)| *interval == pos.interval())
+        {
+            Some((_, list)) => list.push(fret_pos),
+            None => candidates.push((pos.interval(), vec![fret_pos])),
+        }
+    }
+    if candidates.is_empty() || candidates.len() > tuning.strings.len() {
+        return vec![];
+    }
+
+    let candidates: Vec<Vec<FretPosition>> = candidates.into_iter().map(|(_, list)| list).collect();
+    let mut shapes = playable_groups(&candidates, weights.hand_span.as_ref());
+    shapes.sort_by(|a, b| weights.group_cost(a).total_cmp(&weights.group_cost(b)));
+    shapes
+}
+
+/// WASM公開API: 和音列の運指計算
+/// steps: 同時に鳴らす音の配列の配列（例: [[0, 7], [5, 12]]）
+#[wasm_bindgen]
+pub fn calculate_polyphonic_fingering(
+    steps: JsValue,
+    mode: &str,
+    tuning_name: &str,
+) -> Result<JsValue, JsValue> {
+    let fingering_mode = parse_mode(mode)?;
+    let tuning = parse_tuning(tuning_name)?;
+    let steps: Vec<Vec<i32>> = from_js(steps)?;
+
+    let pattern = calculate_polyphonic(&steps, &tuning, &fingering_mode.weights())?;
+    Ok(to_js(&pattern)?)
+}
+
+/// WASM公開API: コードの弾ける形の一覧（コストの低い順）
+#[wasm_bindgen]
+pub fn get_chord_shapes(chord: &str, mode: &str, tuning_name: &str) -> Result<JsValue, JsValue> {
+    let fingering_mode = parse_mode(mode)?;
+    let tuning = parse_tuning(tuning_name)?;
+    let shapes = chord_shapes(chord, &tuning, &fingering_mode.weights());
+    Ok(to_js(&shapes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_group_shape() {
+        let group = PositionGroup::new(vec![FretPosition::new(4, 3), FretPosition::new(2, 5)]);
+        assert!(group.has_distinct_strings());
+        assert_eq!(group.fret_span(), 2);
+        assert_eq!(group.hand_position(), Some(FretPosition::new(4, 3)));
+        assert!(group.is_playable(None));
+
+        // 同じ弦に2音は弾けない
+        let group = PositionGroup::new(vec![FretPosition::new(4, 3), FretPosition::new(4, 5)]);
+        assert!(!group.is_playable(None));
+
+        // 開放弦はフレット幅に含めない
+        let group = PositionGroup::new(vec![FretPosition::new(4, 0), FretPosition::new(3, 7)]);
+        assert_eq!(group.fret_span(), 0);
+        assert!(group.is_playable(None));
+
+        // 1フレットと6フレットは手に収まらない
+        let group = PositionGroup::new(vec![FretPosition::new(4, 1), FretPosition::new(3, 6)]);
+        assert!(!group.is_playable(None));
+        assert!(!group.is_playable(Some(&HandSpan::long_scale())));
+        assert!(PositionGroup::new(vec![]).hand_position().is_none());
+    }
+
+    #[test]
+    fn test_double_stop_groups() {
+        let tuning = Tuning::bass_4();
+        // G1 + B2（10度）
+        let groups = generate_groups(&[3, 19], &tuning, None);
+        assert!(!groups.is_empty());
+        for group in &groups {
+            assert!(group.is_playable(None));
+            for (pos, pitch) in group.positions.iter().zip([3, 19]) {
+                assert_eq!(pos.absolute_pitch(&tuning), pitch);
+            }
+        }
+        assert!(groups.contains(&PositionGroup::new(vec![
+            FretPosition::new(4, 3),
+            FretPosition::new(1, 4),
+        ])));
+
+        // 同音のユニゾンは別の弦
+        let groups = generate_groups(&[10, 10], &tuning, None);
+        assert!(groups.iter().all(|g| g.has_distinct_strings()));
+        assert!(!groups.is_empty());
+    }
+
+    #[test]
+    fn test_polyphonic_sequence() {
+        let tuning = Tuning::bass_4();
+        let weights = AlgorithmWeights::balanced();
+        // E5 → A5 → G（単音）
+        let steps = vec![vec![0, 7], vec![5, 12], vec![15]];
+        let pattern = calculate_polyphonic(&steps, &tuning, &weights).unwrap();
+
+        assert_eq!(pattern.groups.len(), 3);
+        assert_eq!(
+            pattern.groups[0].positions,
+            vec![FretPosition::new(4, 0), FretPosition::new(3, 2)]
+        );
+        for (group, pitches) in pattern.groups.iter().zip(&steps) {
+            assert!(group.is_playable(None));
+            for (pos, &pitch) in group.positions.iter().zip(pitches) {
+                assert_eq!(pos.absolute_pitch(&tuning), pitch);
+            }
+        }
+        assert!(pattern.score.is_finite());
+        assert_eq!(pattern.algorithm, "polyphonic");
+    }
+
+    #[test]
+    fn test_single_notes_match_optimal() {
+        use crate::instrument::fingering::optimal::calculate_optimal;
+
+        let tuning = Tuning::bass_4();
+        let weights = AlgorithmWeights::balanced();
+        let pitches = [3, 15, 8, 20, 10, 5];
+        let steps: Vec<Vec<i32>> = pitches.iter().map(|&p| vec![p]).collect();
+
+        let pattern = calculate_polyphonic(&steps, &tuning, &weights).unwrap();
+        let optimal = calculate_optimal(&pitches, &tuning, &weights).unwrap();
+        assert!((pattern.score - optimal.score).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_unplayable_shapes() {
+        let tuning = Tuning::bass_4();
+        let weights = AlgorithmWeights::balanced();
+
+        // E1 と F1 はどちらも E弦でしか弾けない
+        assert_eq!(
+            calculate_polyphonic(&[vec![5], vec![0, 1]], &tuning, &weights).unwrap_err(),
+            SidFretError::UnplayableShape { index: 1 }
+        );
+        // 音域外
+        assert_eq!(
+            calculate_polyphonic(&[vec![0, 99]], &tuning, &weights).unwrap_err(),
+            SidFretError::PitchOutOfRange {
+                index: 0,
+                pitch: 99
+            }
+        );
+    }
+
+    #[test]
+    fn test_chord_shapes() {
+        let tuning = Tuning::bass_4();
+        let weights = AlgorithmWeights::balanced();
+
+        let shapes = chord_shapes("C", &tuning, &weights);
+        assert!(!shapes.is_empty());
+        for shape in &shapes {
+            assert_eq!(shape.positions.len(), 3);
+            assert!(shape.is_playable(None));
+        }
+        // コストの低い順
+        for pair in shapes.windows(2) {
+            assert!(weights.group_cost(&pair[0]) <= weights.group_cost(&pair[1]));
+        }
+
+        // 構成音が弦の数より多い
+        assert!(chord_shapes("ALL_KEYS", &tuning, &weights).is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+use super::algorithm::{fingering_pattern, parse_mode, parse_tuning, FingeringMode};
+use super::position::FingeringPattern;
+use crate::core::pitch::{absolute_semitone, E1_ABSOLUTE_SEMITONE, MIDI_C0};
+use crate::error::{to_js, SidFretError};
+use crate::instrument::tuning::Tuning;
+use wasm_bindgen::prelude::*;
+
+/// E1 の MIDI ノート番号（28）
+const E1_MIDI: i32 = E1_ABSOLUTE_SEMITONE + MIDI_C0;
+
+/// ピッチ文字列（例: "E♭1"）を E1=0 基準の半音値に変換
+pub fn pitch_from_name(pitch: &str) -> Option<i32> {
+    absolute_semitone(pitch).map(|semitone| semitone - E1_ABSOLUTE_SEMITONE)
+}
+
+/// MIDI ノート番号（E1 = 28）を E1=0 基準の半音値に変換
+pub fn pitch_from_midi(note: u8) -> i32 {
+    note as i32 - E1_MIDI
+}
+
+/// E1=0 基準の半音値を MIDI ノート番号に変換
+pub fn pitch_to_midi(pitch: i32) -> i32 {
+    pitch + E1_MIDI
+}
+
+/// ピッチ文字列の配列を E1=0 基準の半音値に変換（解釈できない音はエラー）
+pub fn pitches_from_names<S: AsRef<str>>(names: &[S]) -> Result<Vec<i32>, SidFretError> {
+    names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            pitch_from_name(name.as_ref()).ok_or_else(|| SidFretError::InvalidPitch {
+                index,
+                input: name.as_ref().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// ピッチ文字列から運指を計算
+pub fn fingering_from_names<S: AsRef<str>>(
+    names: &[S],
+    mode: FingeringMode,
+    tuning: &Tuning,
+) -> Result<FingeringPattern, SidFretError> {
+    let pitches = pitches_from_names(names)?;
+    fingering_pattern(&pitches, mode, tuning)
+}
+
+/// MIDI ノート番号から運指を計算
+pub fn fingering_from_midi(
+    notes: &[u8],
+    mode: FingeringMode,
+    tuning: &Tuning,
+) -> Result<FingeringPattern, SidFretError> {
+    let pitches: Vec<i32> = notes.iter().map(|&note| pitch_from_midi(note)).collect();
+    fingering_pattern(&pitches, mode, tuning)
+}
+
+/// WASM公開API: ピッチ文字列（例: ["E1", "E♭1", "A1"]）から運指計算
+#[wasm_bindgen]
+pub fn calculate_fingering_from_names(
+    pitches: Vec<String>,
+    mode: &str,
+    tuning_name: &str,
+) -> Result<JsValue, JsValue> {
+    let fingering_mode = parse_mode(mode)?;
+    let tuning = parse_tuning(tuning_name)?;
+
+    let pattern = fingering_from_names(&pitches, fingering_mode, &tuning)?;
+    Ok(to_js(&pattern)?)
+}
+
+/// WASM公開API: MIDI ノート番号（E1 = 28）から運指計算
+#[wasm_bindgen]
+pub fn calculate_fingering_from_midi(
+    notes: Vec<u8>,
+    mode: &str,
+    tuning_name: &str,
+) -> Result<JsValue, JsValue> {
+    let fingering_mode = parse_mode(mode)?;
+    let tuning = parse_tuning(tuning_name)?;
+
+    let pattern = fingering_from_midi(&notes, fingering_mode, &tuning)?;
+    Ok(to_js(&pattern)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::pitch::Pitch;
+    use crate::instrument::fingering::position::FretPosition;
+
+    #[test]
+    fn test_pitch_conversion() {
+        assert_eq!(pitch_from_name("E1"), Some(0));
+        assert_eq!(pitch_from_name("E♭1"), Some(-1));
+        assert_eq!(pitch_from_name("B0"), Some(-5));
+        assert_eq!(pitch_from_name("G2"), Some(15));
+        assert_eq!(pitch_from_name("X9"), None);
+
+        assert_eq!(pitch_from_midi(28), 0);
+        assert_eq!(pitch_from_midi(43), 15); // G2
+        assert_eq!(pitch_to_midi(-5), 23); // B0
+
+        // core::pitch の MIDI 変換と一致する
+        for midi in 0..=127u8 {
+            let pitch = Pitch::from_midi(midi);
+            assert_eq!(
+                pitch_from_midi(midi),
+                pitch.semitone() - E1_ABSOLUTE_SEMITONE
+            );
+            assert_eq!(pitch_to_midi(pitch_from_midi(midi)), midi as i32);
+        }
+    }
+
+    #[test]
+    fn test_fingering_from_names() {
+        let tuning = Tuning::bass_4();
+        let pattern =
+            fingering_from_names(&["E1", "A1", "D2"], FingeringMode::Optimal, &tuning).unwrap();
+        assert_eq!(pattern.positions[0], FretPosition::new(4, 0));
+        for (pos, pitch) in pattern.positions.iter().zip([0, 5, 10]) {
+            assert_eq!(pos.absolute_pitch(&tuning), pitch);
+        }
+
+        // 名前と MIDI で同じ結果
+        let from_midi =
+            fingering_from_midi(&[28, 33, 38], FingeringMode::Optimal, &tuning).unwrap();
+        assert_eq!(from_midi.positions, pattern.positions);
+    }
+
+    #[test]
+    fn test_out_of_range_reported() {
+        let tuning = Tuning::bass_4();
+        // E♭1 は4弦ベースの音域外
+        assert_eq!(
+            fingering_from_names(&["E1", "E♭1"], FingeringMode::Balanced, &tuning).unwrap_err(),
+            SidFretError::PitchOutOfRange {
+                index: 1,
+                pitch: -1
+            }
+        );
+        // 5弦ベースなら弾ける
+        let pattern =
+            fingering_from_names(&["E1", "E♭1"], FingeringMode::Balanced, &Tuning::bass_5())
+                .unwrap();
+        assert_eq!(pattern.positions.len(), 2);
+
+        assert_eq!(
+            fingering_from_midi(&[28, 127], FingeringMode::Shortest, &tuning).unwrap_err(),
+            SidFretError::PitchOutOfRange {
+                index: 1,
+                pitch: 99
+            }
+        );
+        assert_eq!(
+            fingering_from_names(&["E1", "H2"], FingeringMode::Balanced, &tuning).unwrap_err(),
+            SidFretError::InvalidPitch {
+                index: 1,
+                input: "H2".to_string()
+            }
+        );
+    }
+}
@@ -1,7 +1,29 @@
 pub mod algorithm;
+pub mod constraint;
+pub mod difficulty;
+pub mod finger;
+pub mod group;
+pub mod input;
+pub mod optimal;
 pub mod position;
+pub mod reach;
+pub mod rhythm;
 pub mod scoring;
+pub mod segment;
+pub mod session;
+pub mod technique;
 
 pub use algorithm::*;
+pub use constraint::*;
+pub use difficulty::*;
+pub use finger::*;
+pub use group::*;
+pub use input::*;
+pub use optimal::*;
 pub use position::*;
+pub use reach::*;
+pub use rhythm::*;
 pub use scoring::*;
+pub use segment::*;
+pub use session::*;
+pub use technique::*;
@@ -0,0 +1,620 @@
+use super::algorithm::{candidate_positions, parse_mode, parse_tuning};
+use super::position::{FingeringPattern, FretPosition};
+use super::reach::{HandSpan, HandWindow};
+use super::scoring::{AlgorithmWeights, ScoreBreakdown};
+use crate::error::{from_js, to_js, SidFretError};
+use crate::instrument::tuning::Tuning;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use wasm_bindgen::prelude::*;
+
+/// 部分経路の終端（累積コストと直前の候補・順位）
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PathEntry {
+    cost: f32,
+    prev: usize,
+    prev_rank: usize,
+}
+
+/// table[i][j] = i番目の音を候補jで弾く部分経路（コスト昇順、最大k本）
+pub(crate) type PathColumn = Vec<Vec<PathEntry>>;
+
+/// 動的計画法の状態（押さえる位置と、その時点の手のポジションの範囲）
+/// hand_span 指定時は同じポジションで押さえ続けた範囲を追跡し、手の届かない経路を除外する
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HeldPosition {
+    pub position: FretPosition,
+    pub window: Option<HandWindow>,
+}
+
+impl HeldPosition {
+    /// 手のポジションの範囲 window から pos を押さえた状態（届かなければ None）
+    /// hand_span がなければ範囲は追跡しない
+    pub fn hold(
+        window: Option<HandWindow>,
+        pos: &FretPosition,
+        hand_span: Option<&HandSpan>,
+    ) -> Option<Self> {
+        match hand_span {
+            Some(span) if !span.can_hold(window, pos) => None,
+            Some(_) => Some(Self {
+                position: *pos,
+                window: HandWindow::after(window, pos),
+            }),
+            None => Some(Self {
+                position: *pos,
+                window: None,
+            }),
+        }
+    }
+
+    /// prev から遷移できる状態か
+    pub fn follows(&self, prev: &Self, hand_span: Option<&HandSpan>) -> bool {
+        Self::hold(prev.window, &self.position, hand_span).as_ref() == Some(self)
+    }
+}
+
+/// 直前の音の状態（最初の音は None）から候補ポジションの状態を展開する
+/// 候補の順序は保ち、手の届かない状態は含めない
+pub(crate) fn held_positions(
+    prev: Option<&[HeldPosition]>,
+    candidates: &[FretPosition],
+    hand_span: Option<&HandSpan>,
+) -> Vec<HeldPosition> {
+    let windows: Vec<Option<HandWindow>> = match prev {
+        Some(states) => states.iter().map(|state| state.window).collect(),
+        None => vec![None],
+    };
+    let mut states = Vec::new();
+    for pos in candidates {
+        for &window in &windows {
+            if let Some(state) = HeldPosition::hold(window, pos, hand_span) {
+                if !states.contains(&state) {
+                    states.push(state);
+                }
+            }
+        }
+    }
+    states
+}
+
+fn by_cost(a: &PathEntry, b: &PathEntry) -> Ordering {
+    a.cost.partial_cmp(&b.cost).unwrap_or(Ordering::Equal)
+}
+
+/// 動的計画法（リストViterbi）で候補列からコストの小さい順に最大k本の経路を求める
+/// candidates[i] は i 番目の音の候補ポジション。空の候補があれば空のVecを返す
+/// base_position を指定すると、そのポジションから最初の音への移動もコストに含める
+pub fn k_best_paths(
+    candidates: &[Vec<FretPosition>],
+    weights: &AlgorithmWeights,
+    base_position: Option<u8>,
+    k: usize,
+) -> Vec<(Vec<FretPosition>, f32)> {
+    k_best_timed_paths(candidates, weights, base_position, &[], k)
+}
+
+/// 音の間隔を考慮して k_best_paths と同様に経路を求める
+/// intervals[i] は i 番目の音から次の音までの時間（秒）。足りない分は基準間隔として扱う
+pub fn k_best_timed_paths(
+    candidates: &[Vec<FretPosition>],
+    weights: &AlgorithmWeights,
+    base_position: Option<u8>,
+    intervals: &[f32],
+    k: usize,
+) -> Vec<(Vec<FretPosition>, f32)> {
+    let hand_span = weights.hand_span.as_ref();
+    let mut states: Vec<Vec<HeldPosition>> = Vec::with_capacity(candidates.len());
+    for (i, positions) in candidates.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|prev| states[prev].as_slice());
+        states.push(held_positions(prev, positions, hand_span));
+    }
+
+    k_best_by(
+        &states,
+        k,
+        |i, state| {
+            let p = &state.position;
+            let entry_cost = match base_position {
+                Some(base) if i == 0 => weights.entry_cost(base, p),
+                _ => 0.0,
+            };
+            weights.position_cost(p) + entry_cost
+        },
+        |i, prev, curr| {
+            if !curr.follows(prev, hand_span) {
+                return f32::INFINITY;
+            }
+            let (prev, curr) = (&prev.position, &curr.position);
+            match intervals.get(i - 1) {
+                Some(&seconds) => weights.timed_transition_cost(prev, curr, seconds),
+                None => weights.transition_cost(prev, curr),
+            }
+        },
+    )
+    .into_iter()
+    .map(|(path, cost)| (path.iter().map(|state| state.position).collect(), cost))
+    .collect()
+}
+
+/// リストViterbiの本体（候補の型とコスト関数を問わない）
+/// unary(i, c) は i 番目の候補 c 単体のコスト、transition(i, prev, curr) は i-1 番目から i 番目への遷移コスト
+/// コストが無限大の経路は除外する
+pub(crate) fn k_best_by<T: Clone>(
+    candidates: &[Vec<T>],
+    k: usize,
+    unary: impl Fn(usize, &T) -> f32,
+    transition: impl Fn(usize, &T, &T) -> f32,
+) -> Vec<(Vec<T>, f32)> {
+    if k == 0 || candidates.iter().any(|c| c.is_empty()) {
+        return vec![];
+    }
+    if candidates.is_empty() {
+        return vec![(vec![], 0.0)];
+    }
+
+    let mut table: Vec<PathColumn> = Vec::with_capacity(candidates.len());
+    table.push(first_column(&candidates[0], |c| unary(0, c)));
+    for i in 1..candidates.len() {
+        let column = next_column(
+            &candidates[i - 1],
+            &table[i - 1],
+            &candidates[i],
+            k,
+            |c| unary(i, c),
+            |prev, curr| transition(i, prev, curr),
+        );
+        table.push(column);
+    }
+
+    trace_paths(candidates, &table, k)
+}
+
+/// 最初の音の列
+pub(crate) fn first_column<T>(candidates: &[T], unary: impl Fn(&T) -> f32) -> PathColumn {
+    candidates
+        .iter()
+        .map(|c| {
+            let cost = unary(c);
+            if cost.is_finite() {
+                vec![PathEntry {
+                    cost,
+                    prev: 0,
+                    prev_rank: 0,
+                }]
+            } else {
+                vec![]
+            }
+        })
+        .collect()
+}
+
+/// 直前の列から次の音の列を求める
+pub(crate) fn next_column<T>(
+    prev_candidates: &[T],
+    prev_column: &PathColumn,
+    candidates: &[T],
+    k: usize,
+    unary: impl Fn(&T) -> f32,
+    transition: impl Fn(&T, &T) -> f32,
+) -> PathColumn {
+    candidates
+        .iter()
+        .map(|curr| {
+            let unary = unary(curr);
+            let mut entries: Vec<PathEntry> = prev_candidates
+                .iter()
+                .enumerate()
+                .flat_map(|(j, prev)| {
+                    let step = transition(prev, curr) + unary;
+                    prev_column[j]
+                        .iter()
+                        .enumerate()
+                        .map(move |(rank, entry)| PathEntry {
+                            cost: entry.cost + step,
+                            prev: j,
+                            prev_rank: rank,
+                        })
+                })
+                .collect();
+            // 手の届かない経路は除外する
+            entries.retain(|entry| entry.cost.is_finite());
+            // 安定ソートなので同コストは低音弦側の候補が優先される
+            entries.sort_by(by_cost);
+            entries.truncate(k);
+            entries
+        })
+        .collect()
+}
+
+/// 終端の全エントリからコストの小さい順にk本をたどる
+pub(crate) fn trace_paths<T: Clone>(
+    candidates: &[Vec<T>],
+    table: &[PathColumn],
+    k: usize,
+) -> Vec<(Vec<T>, f32)> {
+    let Some(last) = candidates.len().checked_sub(1) else {
+        return vec![(vec![], 0.0)];
+    };
+    let mut ends: Vec<(usize, usize, PathEntry)> = table[last]
+        .iter()
+        .enumerate()
+        .flat_map(|(j, entries)| {
+            entries
+                .iter()
+                .enumerate()
+                .map(move |(rank, entry)| (j, rank, *entry))
+        })
+        .collect();
+    ends.sort_by(|a, b| by_cost(&a.2, &b.2));
+    ends.truncate(k);
+
+    ends.into_iter()
+        .map(|(mut j, mut rank, end)| {
+            let mut path = Vec::with_capacity(candidates.len());
+            for i in (0..candidates.len()).rev() {
+                path.push(candidates[i][j].clone());
+                let entry = table[i][j][rank];
+                j = entry.prev;
+                rank = entry.prev_rank;
+            }
+            path.reverse();
+            (path, end.cost)
+        })
+        .collect()
+}
+
+/// 動的計画法（Viterbi）で候補列から最小コストの経路を求める
+/// candidates[i] は i 番目の音の候補ポジション。空の候補があれば None
+pub fn optimal_path(
+    candidates: &[Vec<FretPosition>],
+    weights: &AlgorithmWeights,
+) -> Option<(Vec<FretPosition>, f32)> {
+    k_best_paths(candidates, weights, None, 1)
+        .into_iter()
+        .next()
+}
+
+/// 候補列から最小コストの経路を求める（手の届く経路がなければ最後の音で不成立とする）
+fn best_path(
+    candidates: &[Vec<FretPosition>],
+    weights: &AlgorithmWeights,
+    base_position: Option<u8>,
+) -> Result<Vec<FretPosition>, SidFretError> {
+    k_best_paths(candidates, weights, base_position, 1)
+        .into_iter()
+        .next()
+        .map(|(path, _)| path)
+        .ok_or(SidFretError::InfeasibleConstraint {
+            index: candidates.len().saturating_sub(1),
+        })
+}
+
+/// 最適運指アルゴリズム（フレーズ全体を動的計画法で最適化）
+pub fn calculate_optimal(
+    pitches: &[i32],
+    tuning: &Tuning,
+    weights: &AlgorithmWeights,
+) -> Result<FingeringPattern, SidFretError> {
+    let candidates = candidate_positions(pitches, tuning)?;
+    let positions = best_path(&candidates, weights, None)?;
+
+    let mut pattern = FingeringPattern::new(positions, "optimal".to_string());
+    pattern.score = weights.calculate_score(&pattern);
+    Ok(pattern)
+}
+
+/// ユーザー定義の重みで最適運指を計算
+/// base_position を指定すると、その位置に手を置いた状態から弾き始める
+pub fn calculate_with_weights(
+    pitches: &[i32],
+    tuning: &Tuning,
+    weights: &AlgorithmWeights,
+    base_position: Option<u8>,
+) -> Result<FingeringPattern, SidFretError> {
+    let candidates = candidate_positions(pitches, tuning)?;
+    let positions = best_path(&candidates, weights, base_position)?;
+
+    let mut pattern = FingeringPattern::new(positions, "custom".to_string());
+    pattern.score = weights.calculate_score(&pattern);
+    Ok(pattern)
+}
+
+/// WASM公開API: ユーザー定義の重みで運指計算
+/// weights: AlgorithmWeights と同じ形のオブジェクト（省略したフィールドはbalancedの値）
+#[wasm_bindgen]
+pub fn calculate_fingering_with_weights(
+    pitches: Vec<i32>,
+    weights: JsValue,
+    base_position: Option<u8>,
+    tuning_name: &str,
+) -> Result<JsValue, JsValue> {
+    let weights: AlgorithmWeights = from_js(weights)?;
+    let tuning = parse_tuning(tuning_name)?;
+
+    let pattern = calculate_with_weights(&pitches, &tuning, &weights, base_position)?;
+    Ok(to_js(&pattern)?)
+}
+
+/// 順位付きの運指候補
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedFingering {
+    /// 順位（1=最良）
+    pub rank: usize,
+    pub pattern: FingeringPattern,
+    pub breakdown: ScoreBreakdown,
+}
+
+/// スコア上位k件の運指候補を計算（互いに異なる運指、スコア昇順）
+pub fn calculate_k_best(
+    pitches: &[i32],
+    tuning: &Tuning,
+    weights: &AlgorithmWeights,
+    k: usize,
+) -> Result<Vec<RankedFingering>, SidFretError> {
+    let candidates = candidate_positions(pitches, tuning)?;
+    let paths = k_best_paths(&candidates, weights, None, k);
+    if k > 0 && paths.is_empty() {
+        return Err(SidFretError::InfeasibleConstraint {
+            index: candidates.len().saturating_sub(1),
+        });
+    }
+
+    Ok(paths
+        .into_iter()
+        .enumerate()
+        .map(|(i, (path, _))| {
+            let mut pattern = FingeringPattern::new(path, "optimal".to_string());
+            pattern.score = weights.calculate_score(&pattern);
+            RankedFingering {
+                rank: i + 1,
+                breakdown: ScoreBreakdown::from_pattern(&pattern),
+                pattern,
+            }
+        })
+        .collect())
+}
+
+/// WASM公開API: スコア上位k件の運指候補（モードの重みで順位付け）
+#[wasm_bindgen]
+pub fn calculate_fingering_alternatives(
+    pitches: Vec<i32>,
+    mode: &str,
+    k: usize,
+    tuning_name: &str,
+) -> Result<JsValue, JsValue> {
+    let fingering_mode = parse_mode(mode)?;
+    let tuning = parse_tuning(tuning_name)?;
+
+    let ranked = calculate_k_best(&pitches, &tuning, &fingering_mode.weights(), k)?;
+    Ok(to_js(&ranked)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instrument::fingering::algorithm::{
+        calculate_balanced, calculate_open_string, calculate_shortest_path,
+        calculate_string_priority, generate_all_positions,
+    };
+    use crate::instrument::fingering::reach::HandSpan;
+
+    #[test]
+    fn test_optimal_respects_hand_span() {
+        let tuning = Tuning::bass_4();
+        // F1 → C＃2: 制約なしなら A弦4フレット（同一ポジション）
+        let pattern = calculate_optimal(&[1, 9], &tuning, &AlgorithmWeights::balanced()).unwrap();
+        assert_eq!(pattern.positions[1], FretPosition::new(3, 4));
+
+        // 手が小さいと1-4フレットに届かないので E弦9フレットへ移動
+        let weights = AlgorithmWeights::balanced().with_hand_span(HandSpan::new(864.0, 100.0));
+        let pattern = calculate_optimal(&[1, 9], &tuning, &weights).unwrap();
+        assert_eq!(pattern.positions[1], FretPosition::new(4, 9));
+        assert!(pattern.score.is_finite());
+
+        // 他に選択肢がない場合はエラー
+        assert_eq!(
+            calculate_optimal(&[1, 4], &tuning, &weights).unwrap_err(),
+            SidFretError::InfeasibleConstraint { index: 1 }
+        );
+        assert!(calculate_k_best(&[1, 9], &tuning, &weights, 5)
+            .unwrap()
+            .iter()
+            .all(|r| r.pattern.score.is_finite()));
+    }
+
+    #[test]
+    fn test_optimal_tracks_hand_window() {
+        let tuning = Tuning::bass_4();
+        // F1 → G＃1 → D2: 制約なしなら E弦1・4フレットから A弦5フレット
+        let weights = AlgorithmWeights::shortest();
+        let pattern = calculate_optimal(&[1, 4, 10], &tuning, &weights).unwrap();
+        assert_eq!(pattern.positions[2], FretPosition::new(3, 5));
+
+        // 隣り合う2音はどれも届くが、同じポジションで1-5フレットは標準の手でも届かない
+        let weights = weights.with_hand_span(HandSpan::long_scale());
+        let pattern = calculate_optimal(&[1, 4, 10], &tuning, &weights).unwrap();
+        assert_eq!(pattern.positions[2], FretPosition::new(2, 0));
+        assert!(pattern.score.is_finite());
+    }
+
+    /// 全組み合わせを総当たりして最小スコアを求める
+    fn brute_force_best(pitches: &[i32], tuning: &Tuning, weights: &AlgorithmWeights) -> f32 {
+        fn search(
+            candidates: &[Vec<FretPosition>],
+            current: &mut Vec<FretPosition>,
+            weights: &AlgorithmWeights,
+            best: &mut f32,
+        ) {
+            if current.len() == candidates.len() {
+                let pattern = FingeringPattern::new(current.clone(), "test".to_string());
+                *best = best.min(weights.calculate_score(&pattern));
+                return;
+            }
+            for p in &candidates[current.len()] {
+                current.push(*p);
+                search(candidates, current, weights, best);
+                current.pop();
+            }
+        }
+
+        let candidates: Vec<Vec<FretPosition>> = pitches
+            .iter()
+            .map(|&p| generate_all_positions(p, tuning))
+            .collect();
+        let mut best = f32::INFINITY;
+        search(&candidates, &mut vec![], weights, &mut best);
+        best
+    }
+
+    #[test]
+    fn test_optimal_matches_brute_force() {
+        let tuning = Tuning::bass_4();
+        let phrases: [&[i32]; 3] = [
+            &[0, 5, 7, 10, 12],
+            &[3, 15, 8, 20, 10, 5],
+            &[12, 17, 22, 19, 14],
+        ];
+
+        for pitches in phrases {
+            for weights in [
+                AlgorithmWeights::shortest(),
+                AlgorithmWeights::position_stable(),
+                AlgorithmWeights::open_string(),
+                AlgorithmWeights::string_priority(),
+                AlgorithmWeights::balanced(),
+            ] {
+                let pattern = calculate_optimal(pitches, &tuning, &weights).unwrap();
+                let expected = brute_force_best(pitches, &tuning, &weights);
+                assert!(
+                    (pattern.score - expected).abs() < 1e-4,
+                    "pitches={pitches:?} score={} expected={expected}",
+                    pattern.score
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_optimal_not_worse_than_greedy() {
+        let tuning = Tuning::bass_4();
+        let pitches = vec![0, 2, 4, 5, 7, 9, 11, 12, 14, 16, 17, 19, 21, 23, 24];
+        let weights = AlgorithmWeights::balanced();
+        let optimal = calculate_optimal(&pitches, &tuning, &weights).unwrap();
+
+        for greedy in [
+            calculate_shortest_path(&pitches, &tuning, None).unwrap(),
+            calculate_open_string(&pitches, &tuning, None).unwrap(),
+            calculate_string_priority(&pitches, &tuning, None).unwrap(),
+            calculate_balanced(&pitches, &tuning, None).unwrap(),
+        ] {
+            assert!(optimal.score <= weights.calculate_score(&greedy) + 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_optimal_path_edge_cases() {
+        let weights = AlgorithmWeights::balanced();
+        assert_eq!(optimal_path(&[], &weights), Some((vec![], 0.0)));
+        assert!(k_best_paths(&[vec![FretPosition::new(4, 0)]], &weights, None, 0).is_empty());
+        assert_eq!(
+            optimal_path(&[vec![FretPosition::new(4, 0)], vec![]], &weights),
+            None
+        );
+
+        assert_eq!(
+            calculate_optimal(&[0, 99], &Tuning::bass_4(), &weights).unwrap_err(),
+            SidFretError::PitchOutOfRange {
+                index: 1,
+                pitch: 99
+            }
+        );
+    }
+
+    #[test]
+    fn test_k_best_ranked_and_distinct() {
+        let tuning = Tuning::bass_4();
+        let pitches = vec![5, 7, 9, 10, 12];
+        let weights = AlgorithmWeights::balanced();
+        let ranked = calculate_k_best(&pitches, &tuning, &weights, 5).unwrap();
+
+        assert_eq!(ranked.len(), 5);
+        for (i, r) in ranked.iter().enumerate() {
+            assert_eq!(r.rank, i + 1);
+            assert_eq!(r.breakdown, ScoreBreakdown::from_pattern(&r.pattern));
+            for (pos, &pitch) in r.pattern.positions.iter().zip(&pitches) {
+                assert_eq!(pos.absolute_pitch(&tuning), pitch);
+            }
+        }
+        // スコア昇順
+        for pair in ranked.windows(2) {
+            assert!(pair[0].pattern.score <= pair[1].pattern.score + 1e-4);
+        }
+        // 互いに異なる運指
+        for i in 0..ranked.len() {
+            for j in i + 1..ranked.len() {
+                assert_ne!(ranked[i].pattern.positions, ranked[j].pattern.positions);
+            }
+        }
+        // 1位は最適運指と一致
+        let optimal = calculate_optimal(&pitches, &tuning, &weights).unwrap();
+        assert_eq!(ranked[0].pattern.positions, optimal.positions);
+    }
+
+    #[test]
+    fn test_k_best_exhausts_all_combinations() {
+        // E1（1通り）→ A1（2通り）→ D2（3通り）= 6通り
+        let tuning = Tuning::bass_4();
+        let ranked =
+            calculate_k_best(&[0, 5, 10], &tuning, &AlgorithmWeights::balanced(), 10).unwrap();
+        assert_eq!(ranked.len(), 6);
+        assert!(
+            calculate_k_best(&[0, 5], &tuning, &AlgorithmWeights::balanced(), 0)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_calculate_with_weights_base_position() {
+        let tuning = Tuning::bass_4();
+        let weights = AlgorithmWeights::position_stable();
+        // A2（17半音）→ B2: 同コストの候補が並ぶので指定がなければ低音弦側
+        let pattern = calculate_with_weights(&[17, 19], &tuning, &weights, None).unwrap();
+        assert_eq!(pattern.algorithm, "custom");
+        assert_eq!(pattern.positions[0], FretPosition::new(4, 17));
+
+        // ローポジションから始めるなら G弦2フレット
+        let pattern = calculate_with_weights(&[17, 19], &tuning, &weights, Some(1)).unwrap();
+        assert_eq!(pattern.positions[0], FretPosition::new(1, 2));
+
+        // 12フレット付近から始めるなら A弦12フレット
+        let pattern = calculate_with_weights(&[17, 19], &tuning, &weights, Some(12)).unwrap();
+        assert_eq!(pattern.positions[0], FretPosition::new(3, 12));
+        for (pos, pitch) in pattern.positions.iter().zip([17, 19]) {
+            assert_eq!(pos.absolute_pitch(&tuning), pitch);
+        }
+    }
+
+    #[test]
+    fn test_custom_weights_prefer_open_strings() {
+        let tuning = Tuning::bass_4();
+        let weights = AlgorithmWeights {
+            open_string_weight: -20.0,
+            ..AlgorithmWeights::balanced()
+        };
+        let pattern = calculate_with_weights(&[5, 10, 15], &tuning, &weights, None).unwrap();
+        assert_eq!(pattern.open_string_count(), 3);
+    }
+
+    #[test]
+    fn test_optimal_with_5string() {
+        let tuning = Tuning::bass_5();
+        let pitches = vec![-5, -3, -1, 0];
+        let pattern = calculate_optimal(&pitches, &tuning, &AlgorithmWeights::balanced()).unwrap();
+        assert_eq!(pattern.algorithm, "optimal");
+        for (pos, &pitch) in pattern.positions.iter().zip(&pitches) {
+            assert_eq!(pos.absolute_pitch(&tuning), pitch);
+        }
+    }
+}
@@ -1,14 +1,23 @@
-use serde::{Deserialize, Serialize};
+use super::segment::{position_segments, PositionSegment};
+use super::technique::Technique;
+use crate::instrument::tuning::Tuning;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+
+/// 1つのポジションに含まれるフレット数（1指1フレット）
+pub const POSITION_WIDTH: u8 = 4;
 
 /// ベースのフレットポジション
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FretPosition {
-    /// 弦番号（1=G弦, 2=D弦, 3=A弦, 4=E弦）
+    /// 弦番号（1=最高音弦、4弦ベースなら 1=G弦, 2=D弦, 3=A弦, 4=E弦）
     pub string: u8,
     /// フレット番号（0=開放弦）
     pub fret: u8,
     /// 推奨される指番号（1=人差し指, 2=中指, 3=薬指, 4=小指）
     pub finger: Option<u8>,
+    /// 奏法（ハンマリング・プリング・スライドは前の音からのつなぎ方）
+    pub technique: Option<Technique>,
 }
 
 impl FretPosition {
@@ -17,6 +26,7 @@ impl FretPosition {
             string,
             fret,
             finger: None,
+            technique: None,
         }
     }
 
@@ -25,36 +35,63 @@ impl FretPosition {
         self
     }
 
+    pub fn with_technique(mut self, technique: Technique) -> Self {
+        self.technique = Some(technique);
+        self
+    }
+
     /// ポジション（フレット範囲）を取得（例：5フレット付近 = ポジション5）
     pub fn position(&self) -> u8 {
         if self.fret == 0 {
             0
         } else {
-            ((self.fret - 1) / 4) * 4 + 1
+            ((self.fret - 1) / POSITION_WIDTH) * POSITION_WIDTH + 1
+        }
+    }
+
+    /// 次の音への移動距離（同じ弦はフレット差、弦移動はペナルティ + フレット差の半分）
+    pub fn movement_to(&self, next: &FretPosition) -> u32 {
+        let fret_dist = (self.fret as i32 - next.fret as i32).unsigned_abs();
+        if self.string == next.string {
+            fret_dist
+        } else {
+            1 + fret_dist / 2
         }
     }
 
-    /// 半音階での絶対位置を取得（E弦0フレット = 0）
-    pub fn absolute_pitch(&self) -> u8 {
-        let string_offset = match self.string {
-            1 => 15, // G弦
-            2 => 10, // D弦
-            3 => 5,  // A弦
-            4 => 0,  // E弦
-            _ => 0,
-        };
-        string_offset + self.fret
+    /// 次の音でポジション変更が発生するか（開放弦はどのポジションからも弾ける）
+    pub fn is_position_change(&self, next: &FretPosition) -> bool {
+        let prev_pos = self.position();
+        let curr_pos = next.position();
+        prev_pos != curr_pos && curr_pos != 0 && prev_pos != 0
+    }
+
+    /// 半音階での絶対位置を取得（E1 = 0、チューニング対応）
+    pub fn absolute_pitch(&self, tuning: &Tuning) -> i32 {
+        tuning.string_offset(self.string).unwrap_or(0) + self.fret as i32
     }
 }
 
 /// 運指パターン
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// serde では positions から求めた segments も出力する（入力の segments は読み捨てる）
+#[derive(Debug, Clone, Deserialize)]
 pub struct FingeringPattern {
     pub positions: Vec<FretPosition>,
     pub score: f32,
     pub algorithm: String,
 }
 
+impl Serialize for FingeringPattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("FingeringPattern", 4)?;
+        state.serialize_field("positions", &self.positions)?;
+        state.serialize_field("score", &self.score)?;
+        state.serialize_field("algorithm", &self.algorithm)?;
+        state.serialize_field("segments", &self.segments())?;
+        state.end()
+    }
+}
+
 impl FingeringPattern {
     pub fn new(positions: Vec<FretPosition>, algorithm: String) -> Self {
         Self {
@@ -64,6 +101,11 @@ impl FingeringPattern {
         }
     }
 
+    /// ポジションごとの区間（ポジション記号・移動の種類）
+    pub fn segments(&self) -> Vec<PositionSegment> {
+        position_segments(&self.positions)
+    }
+
     pub fn with_score(mut self, score: f32) -> Self {
         self.score = score;
         self
@@ -71,34 +113,18 @@ impl FingeringPattern {
 
     /// フレット移動の総距離を計算
     pub fn total_movement(&self) -> u32 {
-        let mut total = 0u32;
-        for i in 1..self.positions.len() {
-            let prev = &self.positions[i - 1];
-            let curr = &self.positions[i];
-
-            // 同じ弦の場合はフレット間距離
-            if prev.string == curr.string {
-                total += (prev.fret as i32 - curr.fret as i32).unsigned_abs();
-            } else {
-                // 異なる弦の場合は弦移動ペナルティ + フレット差
-                total += 1; // 弦移動ペナルティ
-                total += (prev.fret as i32 - curr.fret as i32).unsigned_abs() / 2;
-            }
-        }
-        total
+        self.positions
+            .windows(2)
+            .map(|pair| pair[0].movement_to(&pair[1]))
+            .sum()
     }
 
     /// ポジション変更の回数を計算
     pub fn position_changes(&self) -> u32 {
-        let mut changes = 0u32;
-        for i in 1..self.positions.len() {
-            let prev_pos = self.positions[i - 1].position();
-            let curr_pos = self.positions[i].position();
-            if prev_pos != curr_pos && curr_pos != 0 && prev_pos != 0 {
-                changes += 1;
-            }
-        }
-        changes
+        self.positions
+            .windows(2)
+            .filter(|pair| pair[0].is_position_change(&pair[1]))
+            .count() as u32
     }
 
     /// 開放弦の使用回数を計算
@@ -116,6 +142,54 @@ impl FingeringPattern {
         }
         changes
     }
+
+    /// 押弦音どうしの最大フレット移動（開放弦は除く）
+    pub fn largest_shift(&self) -> u8 {
+        let fretted: Vec<u8> = self
+            .positions
+            .iter()
+            .filter(|p| p.fret != 0)
+            .map(|p| p.fret)
+            .collect();
+        fretted
+            .windows(2)
+            .map(|pair| pair[0].abs_diff(pair[1]))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// ポジションを変えずに押さえる範囲の最大フレット幅（ストレッチ）
+    pub fn max_stretch(&self) -> u8 {
+        let mut max_stretch = 0;
+        let mut range: Option<(u8, u8)> = None;
+        let mut prev: Option<&FretPosition> = None;
+
+        for pos in self.positions.iter().filter(|p| p.fret != 0) {
+            if prev.is_some_and(|p| p.is_position_change(pos)) {
+                range = None;
+            }
+            let (low, high) = range.map_or((pos.fret, pos.fret), |(low, high)| {
+                (low.min(pos.fret), high.max(pos.fret))
+            });
+            max_stretch = max_stretch.max(high - low);
+            range = Some((low, high));
+            prev = Some(pos);
+        }
+        max_stretch
+    }
+
+    /// 隣の弦を飛ばす弦移動の回数
+    pub fn string_skips(&self) -> u32 {
+        self.positions
+            .windows(2)
+            .filter(|pair| pair[0].string.abs_diff(pair[1].string) >= 2)
+            .count() as u32
+    }
+
+    /// 使用する最も高いフレット
+    pub fn highest_fret(&self) -> u8 {
+        self.positions.iter().map(|p| p.fret).max().unwrap_or(0)
+    }
 }
 
 #[cfg(test)]
@@ -124,17 +198,35 @@ mod tests {
 
     #[test]
     fn test_fret_position_absolute_pitch() {
+        let tuning = Tuning::bass_4();
+
         let pos = FretPosition::new(4, 0); // E弦開放
-        assert_eq!(pos.absolute_pitch(), 0);
+        assert_eq!(pos.absolute_pitch(&tuning), 0);
 
         let pos = FretPosition::new(4, 5); // E弦5フレット（A音）
-        assert_eq!(pos.absolute_pitch(), 5);
+        assert_eq!(pos.absolute_pitch(&tuning), 5);
 
         let pos = FretPosition::new(3, 0); // A弦開放
-        assert_eq!(pos.absolute_pitch(), 5);
+        assert_eq!(pos.absolute_pitch(&tuning), 5);
 
         let pos = FretPosition::new(1, 0); // G弦開放
-        assert_eq!(pos.absolute_pitch(), 15);
+        assert_eq!(pos.absolute_pitch(&tuning), 15);
+    }
+
+    #[test]
+    fn test_fret_position_absolute_pitch_with_tuning() {
+        // 5弦ベースの5弦開放 = B0（E1の5半音下）
+        assert_eq!(
+            FretPosition::new(5, 0).absolute_pitch(&Tuning::bass_5()),
+            -5
+        );
+        // 5弦ベースの4弦はE弦
+        assert_eq!(FretPosition::new(4, 3).absolute_pitch(&Tuning::bass_5()), 3);
+        // ドロップDの4弦開放 = D1
+        assert_eq!(
+            FretPosition::new(4, 0).absolute_pitch(&Tuning::bass_drop_d()),
+            -2
+        );
     }
 
     #[test]
@@ -147,6 +239,16 @@ mod tests {
         assert_eq!(FretPosition::new(4, 9).position(), 9); // 9thポジション
     }
 
+    #[test]
+    fn test_fret_position_transitions() {
+        let a = FretPosition::new(4, 3);
+        assert_eq!(a.movement_to(&FretPosition::new(4, 5)), 2); // 同じ弦
+        assert_eq!(a.movement_to(&FretPosition::new(3, 8)), 3); // 弦移動 1 + 5/2
+        assert!(a.is_position_change(&FretPosition::new(4, 5)));
+        assert!(!a.is_position_change(&FretPosition::new(3, 4)));
+        assert!(!a.is_position_change(&FretPosition::new(3, 0))); // 開放弦
+    }
+
     #[test]
     fn test_fingering_pattern_total_movement() {
         let pattern = FingeringPattern::new(
@@ -177,4 +279,48 @@ mod tests {
         assert_eq!(pattern.open_string_count(), 2);
         assert_eq!(pattern.string_changes(), 1);
     }
+    #[test]
+    fn test_fingering_pattern_shape_metrics() {
+        let pattern = FingeringPattern::new(
+            vec![
+                FretPosition::new(4, 1),
+                FretPosition::new(2, 4),  // 弦飛ばし、1-4フレットのストレッチ
+                FretPosition::new(3, 0),  // 開放弦
+                FretPosition::new(3, 9),  // ポジション移動
+                FretPosition::new(1, 10), // 弦飛ばし
+            ],
+            "test".to_string(),
+        );
+
+        assert_eq!(pattern.largest_shift(), 5);
+        assert_eq!(pattern.max_stretch(), 3);
+        assert_eq!(pattern.string_skips(), 2);
+        assert_eq!(pattern.highest_fret(), 10);
+
+        let empty = FingeringPattern::new(vec![], "test".to_string());
+        assert_eq!(empty.largest_shift(), 0);
+        assert_eq!(empty.max_stretch(), 0);
+        assert_eq!(empty.highest_fret(), 0);
+    }
+
+    #[test]
+    fn test_fingering_pattern_segments_follow_positions() {
+        let mut pattern = FingeringPattern::new(
+            vec![FretPosition::new(4, 1), FretPosition::new(4, 3)],
+            "test".to_string(),
+        );
+        assert_eq!(pattern.segments().len(), 1);
+
+        // 変更後の positions から求め直す
+        pattern.positions.push(FretPosition::new(3, 9));
+        let json = serde_json::to_value(&pattern).unwrap();
+        assert_eq!(json["segments"].as_array().map(Vec::len), Some(2));
+        assert_eq!(json["segments"][1]["marker"], "IX");
+
+        // 入力の segments は使わない
+        let json = r#"{"positions":[{"string":4,"fret":5,"finger":null,"technique":null}],
+            "score":0.0,"algorithm":"test","segments":[]}"#;
+        let pattern: FingeringPattern = serde_json::from_str(json).unwrap();
+        assert_eq!(pattern.segments().len(), 1);
+    }
 }
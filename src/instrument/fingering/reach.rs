@@ -0,0 +1,221 @@
+use super::position::{FingeringPattern, FretPosition};
+use serde::{Deserialize, Serialize};
+
+/// 手を動かさずに押さえ続けられるフレット幅の上限（これを超える移動はポジション移動）
+const MAX_STRETCH_FRETS: u8 = 4;
+
+/// 現在の手のポジションで押さえたフレットの範囲（開放弦は含まない）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandWindow {
+    pub low: u8,
+    pub high: u8,
+}
+
+impl HandWindow {
+    /// 直前の範囲から pos を押さえた後の範囲
+    /// 開放弦は範囲を変えず、MAX_STRETCH_FRETS を超える場合は pos の位置へポジション移動する
+    pub fn after(window: Option<Self>, pos: &FretPosition) -> Option<Self> {
+        if pos.fret == 0 {
+            return window;
+        }
+        let moved = Self {
+            low: pos.fret,
+            high: pos.fret,
+        };
+        Some(match window {
+            Some(window) => {
+                let low = window.low.min(pos.fret);
+                let high = window.high.max(pos.fret);
+                if high - low > MAX_STRETCH_FRETS {
+                    moved
+                } else {
+                    Self { low, high }
+                }
+            }
+            None => moved,
+        })
+    }
+}
+
+/// 楽器のスケール長と手の大きさに基づく物理的な到達範囲
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HandSpan {
+    /// スケール長（mm、ロングスケール = 864mm）
+    pub scale_length: f32,
+    /// 同一ポジション内で押さえられる最大幅（mm）
+    pub max_span: f32,
+}
+
+impl Default for HandSpan {
+    fn default() -> Self {
+        Self::long_scale()
+    }
+}
+
+impl HandSpan {
+    pub fn new(scale_length: f32, max_span: f32) -> Self {
+        Self {
+            scale_length,
+            max_span,
+        }
+    }
+
+    /// ロングスケール（34インチ）
+    pub fn long_scale() -> Self {
+        Self::new(864.0, 140.0)
+    }
+
+    /// ショートスケール（30インチ）
+    pub fn short_scale() -> Self {
+        Self::new(762.0, 140.0)
+    }
+
+    /// ナットからフレットまでの距離（mm）
+    pub fn fret_distance(&self, fret: u8) -> f32 {
+        self.scale_length * (1.0 - 2f32.powf(-(fret as f32) / 12.0))
+    }
+
+    /// 2つのフレット間の距離（mm）
+    pub fn span_between(&self, fret_a: u8, fret_b: u8) -> f32 {
+        (self.fret_distance(fret_a) - self.fret_distance(fret_b)).abs()
+    }
+
+    /// 1フレット目の幅（移動コストの単位）
+    fn unit(&self) -> f32 {
+        self.fret_distance(1)
+    }
+
+    /// 次の音への移動コスト（1フレット目の幅を1とした物理距離）
+    /// 弦移動の扱いは FretPosition::movement_to と同じ
+    pub fn movement(&self, prev: &FretPosition, curr: &FretPosition) -> f32 {
+        let dist = self.span_between(prev.fret, curr.fret) / self.unit();
+        if prev.string == curr.string {
+            dist
+        } else {
+            1.0 + dist / 2.0
+        }
+    }
+
+    /// 範囲内のフレットを手を動かさずに押さえられるか
+    pub fn covers(&self, window: &HandWindow) -> bool {
+        self.span_between(window.low, window.high) <= self.max_span
+    }
+
+    /// 現在の手のポジションの範囲から pos を押さえられるか
+    pub fn can_hold(&self, window: Option<HandWindow>, pos: &FretPosition) -> bool {
+        HandWindow::after(window, pos).is_none_or(|window| self.covers(&window))
+    }
+
+    /// 2音を続けて押さえられるか（開放弦とポジション移動を伴う場合は常に到達可能）
+    pub fn is_reachable(&self, prev: &FretPosition, curr: &FretPosition) -> bool {
+        self.can_hold(HandWindow::after(None, prev), curr)
+    }
+
+    /// 運指パターン全体の物理的な移動コスト
+    pub fn total_movement(&self, pattern: &FingeringPattern) -> f32 {
+        pattern
+            .positions
+            .windows(2)
+            .map(|pair| self.movement(&pair[0], &pair[1]))
+            .sum()
+    }
+
+    /// 運指パターン全体が手の届く範囲に収まっているか
+    /// 同じ手のポジションで押さえた最低・最高フレットの幅で判定する
+    pub fn is_playable(&self, pattern: &FingeringPattern) -> bool {
+        let mut window = None;
+        pattern.positions.iter().all(|pos| {
+            let reachable = self.can_hold(window, pos);
+            window = HandWindow::after(window, pos);
+            reachable
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fret_distance() {
+        let span = HandSpan::long_scale();
+        assert_eq!(span.fret_distance(0), 0.0);
+        // 12フレットはスケール長の半分
+        assert!((span.fret_distance(12) - 432.0).abs() < 0.01);
+        // フレット間隔は高音側ほど狭い
+        assert!(span.span_between(1, 2) > span.span_between(12, 13));
+    }
+
+    #[test]
+    fn test_movement_depends_on_fret_region() {
+        let span = HandSpan::long_scale();
+        let low = span.movement(&FretPosition::new(4, 1), &FretPosition::new(4, 6));
+        let high = span.movement(&FretPosition::new(4, 12), &FretPosition::new(4, 17));
+        assert!(low > high);
+        // 1フレット目の幅が単位
+        let one = span.movement(&FretPosition::new(4, 0), &FretPosition::new(4, 1));
+        assert!((one - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_is_reachable() {
+        let span = HandSpan::new(864.0, 100.0);
+        // 1-4フレット（約130mm）は届かない、13-16フレット（約65mm）は届く
+        assert!(!span.is_reachable(&FretPosition::new(4, 1), &FretPosition::new(3, 4)));
+        assert!(span.is_reachable(&FretPosition::new(4, 13), &FretPosition::new(3, 16)));
+        // 開放弦とポジション移動は常に可能
+        assert!(span.is_reachable(&FretPosition::new(4, 0), &FretPosition::new(3, 4)));
+        assert!(span.is_reachable(&FretPosition::new(4, 1), &FretPosition::new(4, 9)));
+
+        // 標準の手でも1-5フレット（約169mm）は届かない
+        let default = HandSpan::long_scale();
+        assert!(!default.is_reachable(&FretPosition::new(4, 1), &FretPosition::new(3, 5)));
+        assert!(default.is_reachable(&FretPosition::new(4, 4), &FretPosition::new(3, 5)));
+    }
+
+    #[test]
+    fn test_hand_window() {
+        let window = HandWindow::after(None, &FretPosition::new(4, 3));
+        let window = HandWindow::after(window, &FretPosition::new(3, 0));
+        assert_eq!(window, Some(HandWindow { low: 3, high: 3 }));
+        let window = HandWindow::after(window, &FretPosition::new(3, 1));
+        assert_eq!(window, Some(HandWindow { low: 1, high: 3 }));
+        // 4フレットを超える移動は新しいポジション
+        let window = HandWindow::after(window, &FretPosition::new(2, 7));
+        assert_eq!(window, Some(HandWindow { low: 7, high: 7 }));
+    }
+
+    #[test]
+    fn test_is_playable() {
+        let span = HandSpan::new(864.0, 100.0);
+        let pattern = FingeringPattern::new(
+            vec![FretPosition::new(4, 1), FretPosition::new(3, 4)],
+            "test".to_string(),
+        );
+        assert!(!span.is_playable(&pattern));
+        assert!(HandSpan::long_scale().is_playable(&pattern));
+
+        // 隣り合う2音は届いても、同じポジションで1-5フレットを押さえることになる
+        let spread = FingeringPattern::new(
+            vec![
+                FretPosition::new(4, 1),
+                FretPosition::new(3, 3),
+                FretPosition::new(2, 5),
+            ],
+            "test".to_string(),
+        );
+        assert!(HandSpan::long_scale().is_reachable(&spread.positions[0], &spread.positions[1]));
+        assert!(HandSpan::long_scale().is_reachable(&spread.positions[1], &spread.positions[2]));
+        assert!(!HandSpan::long_scale().is_playable(&spread));
+        // ハイポジションなら同じ形でも届く
+        let high = FingeringPattern::new(
+            vec![
+                FretPosition::new(4, 12),
+                FretPosition::new(3, 14),
+                FretPosition::new(2, 16),
+            ],
+            "test".to_string(),
+        );
+        assert!(HandSpan::long_scale().is_playable(&high));
+    }
+}
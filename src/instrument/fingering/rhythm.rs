@@ -0,0 +1,254 @@
+use super::algorithm::{candidate_positions, parse_mode, parse_tuning};
+use super::optimal::k_best_timed_paths;
+use super::position::FingeringPattern;
+use super::scoring::AlgorithmWeights;
+use crate::error::{from_js, to_js, SidFretError};
+use crate::instrument::tuning::Tuning;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// 発音タイミング付きの音（時間の単位は拍）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NoteEvent {
+    /// E1=0基準の半音値
+    pub pitch: i32,
+    /// 発音開始位置（拍）
+    pub onset: f32,
+    /// 音価（拍）
+    pub duration: f32,
+}
+
+impl NoteEvent {
+    pub fn new(pitch: i32, onset: f32, duration: f32) -> Self {
+        Self {
+            pitch,
+            onset,
+            duration,
+        }
+    }
+
+    /// 発音終了位置（拍）
+    pub fn offset(&self) -> f32 {
+        self.onset + self.duration
+    }
+
+    /// 音価の列から隙間なく並んだ音列を作る（例: [(0, 1.0), (5, 0.5)]）
+    pub fn sequence(notes: &[(i32, f32)]) -> Vec<NoteEvent> {
+        let mut onset = 0.0;
+        notes
+            .iter()
+            .map(|&(pitch, duration)| {
+                let event = NoteEvent::new(pitch, onset, duration);
+                onset += duration;
+                event
+            })
+            .collect()
+    }
+}
+
+/// テンポを検証する（正の有限値でなければエラー）
+pub fn parse_bpm(bpm: f32) -> Result<f32, SidFretError> {
+    if bpm.is_finite() && bpm > 0.0 {
+        Ok(bpm)
+    } else {
+        Err(SidFretError::InvalidInput(format!(
+            "invalid tempo {bpm} bpm"
+        )))
+    }
+}
+
+/// 拍数を秒に変換（bpm は parse_bpm で検証済みの値）
+pub fn beats_to_seconds(beats: f32, bpm: f32) -> f32 {
+    beats * 60.0 / bpm
+}
+
+/// 秒を拍数に変換（bpm は parse_bpm で検証済みの値）
+pub fn seconds_to_beats(seconds: f32, bpm: f32) -> f32 {
+    seconds * bpm / 60.0
+}
+
+/// 各音から次の音までに手を動かせる時間（秒、発音開始の間隔）
+/// 音価は発音中でも手を動かせるため考慮しない。発音開始が有限値でないか前の音より早ければエラー
+pub fn transition_intervals(onsets: &[f32], bpm: f32) -> Result<Vec<f32>, SidFretError> {
+    if let Some(index) = onsets.iter().position(|onset| !onset.is_finite()) {
+        return Err(SidFretError::InvalidInput(format!(
+            "invalid onset {} at index {index}",
+            onsets[index]
+        )));
+    }
+    onsets
+        .windows(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            if pair[1] < pair[0] {
+                Err(SidFretError::InvalidInput(format!(
+                    "onset {} at index {} is earlier than the previous onset {}",
+                    pair[1],
+                    i + 1,
+                    pair[0]
+                )))
+            } else {
+                Ok(beats_to_seconds(pair[1] - pair[0], bpm))
+            }
+        })
+        .collect()
+}
+
+/// リズムとテンポを考慮した最適運指
+/// 速いパッセージほど移動・ポジション変更を避ける
+/// （bpm が正の有限値でないか、発音開始が前の音より早ければエラー）
+pub fn calculate_rhythmic(
+    events: &[NoteEvent],
+    bpm: f32,
+    tuning: &Tuning,
+    weights: &AlgorithmWeights,
+) -> Result<FingeringPattern, SidFretError> {
+    let bpm = parse_bpm(bpm)?;
+    let pitches: Vec<i32> = events.iter().map(|event| event.pitch).collect();
+    let onsets: Vec<f32> = events.iter().map(|event| event.onset).collect();
+    let intervals = transition_intervals(&onsets, bpm)?;
+    let candidates = candidate_positions(&pitches, tuning)?;
+
+    let positions = k_best_timed_paths(&candidates, weights, None, &intervals, 1)
+        .into_iter()
+        .next()
+        .map(|(path, _)| path)
+        .ok_or(SidFretError::InfeasibleConstraint {
+            index: events.len().saturating_sub(1),
+        })?;
+
+    let mut pattern = FingeringPattern::new(positions, "rhythmic".to_string());
+    pattern.score = weights.calculate_timed_score(&pattern, &intervals);
+    Ok(pattern)
+}
+
+/// WASM公開API: リズム・テンポを考慮した運指計算
+/// events: NoteEvent の配列（例: [{ pitch: 0, onset: 0, duration: 0.25 }]）
+#[wasm_bindgen]
+pub fn calculate_fingering_with_rhythm(
+    events: JsValue,
+    bpm: f32,
+    mode: &str,
+    tuning_name: &str,
+) -> Result<JsValue, JsValue> {
+    let fingering_mode = parse_mode(mode)?;
+    let tuning = parse_tuning(tuning_name)?;
+    let events: Vec<NoteEvent> = from_js(events)?;
+
+    let pattern = calculate_rhythmic(&events, bpm, &tuning, &fingering_mode.weights())?;
+    Ok(to_js(&pattern)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instrument::fingering::optimal::calculate_optimal;
+    use crate::instrument::fingering::position::FretPosition;
+
+    #[test]
+    fn test_sequence_and_intervals() {
+        let events = NoteEvent::sequence(&[(0, 1.0), (5, 0.5), (7, 0.5)]);
+        assert_eq!(events[1].onset, 1.0);
+        assert_eq!(events[2].offset(), 2.0);
+
+        let onsets: Vec<f32> = events.iter().map(|event| event.onset).collect();
+        assert_eq!(transition_intervals(&onsets, 120.0), Ok(vec![0.5, 0.25]));
+
+        // 和音のように同時に発音する音は間隔 0
+        assert_eq!(
+            transition_intervals(&[0.0, 0.0, 1.0], 60.0),
+            Ok(vec![0.0, 1.0])
+        );
+    }
+
+    #[test]
+    fn test_unordered_onsets_are_error() {
+        assert!(matches!(
+            transition_intervals(&[0.0, 2.0, 1.0], 120.0),
+            Err(SidFretError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            transition_intervals(&[0.0, f32::NAN], 120.0),
+            Err(SidFretError::InvalidInput(_))
+        ));
+
+        let events = [NoteEvent::new(0, 1.0, 1.0), NoteEvent::new(5, 0.0, 1.0)];
+        assert!(matches!(
+            calculate_rhythmic(
+                &events,
+                120.0,
+                &Tuning::bass_4(),
+                &AlgorithmWeights::balanced()
+            ),
+            Err(SidFretError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_reference_tempo_matches_optimal() {
+        // 120bpmの4分音符は基準間隔なので通常の最適運指と同じ
+        let tuning = Tuning::bass_4();
+        let weights = AlgorithmWeights::balanced();
+        let pitches = [3, 15, 8, 20, 10, 5];
+        let events = NoteEvent::sequence(&pitches.map(|p| (p, 1.0)));
+
+        let rhythmic = calculate_rhythmic(&events, 120.0, &tuning, &weights).unwrap();
+        let optimal = calculate_optimal(&pitches, &tuning, &weights).unwrap();
+        assert_eq!(rhythmic.positions, optimal.positions);
+        assert!((rhythmic.score - optimal.score).abs() < 1e-4);
+        assert_eq!(rhythmic.algorithm, "rhythmic");
+    }
+
+    #[test]
+    fn test_fast_passage_avoids_shifts() {
+        let tuning = Tuning::bass_4();
+        let weights = AlgorithmWeights::balanced();
+        // E1 → G＃1 → B1
+        let pitches = [0, 4, 7];
+
+        let slow = NoteEvent::sequence(&pitches.map(|p| (p, 4.0)));
+        let fast = NoteEvent::sequence(&pitches.map(|p| (p, 0.25)));
+        let slow = calculate_rhythmic(&slow, 60.0, &tuning, &weights).unwrap();
+        let fast = calculate_rhythmic(&fast, 180.0, &tuning, &weights).unwrap();
+
+        // ゆっくりなら E弦上でポジション移動、速ければ弦移動で同じポジションに留まる
+        assert_eq!(slow.positions[2], FretPosition::new(4, 7));
+        assert_eq!(fast.positions[2], FretPosition::new(3, 2));
+        assert!(fast.position_changes() < slow.position_changes());
+    }
+
+    #[test]
+    fn test_out_of_range_event() {
+        let events = NoteEvent::sequence(&[(0, 1.0), (99, 1.0)]);
+        assert_eq!(
+            calculate_rhythmic(
+                &events,
+                120.0,
+                &Tuning::bass_4(),
+                &AlgorithmWeights::balanced()
+            )
+            .unwrap_err(),
+            SidFretError::PitchOutOfRange {
+                index: 1,
+                pitch: 99
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_tempo_is_error() {
+        let events = NoteEvent::sequence(&[(0, 1.0), (5, 1.0)]);
+        assert_eq!(parse_bpm(120.0), Ok(120.0));
+        for bpm in [0.0, -60.0, f32::NAN, f32::INFINITY] {
+            assert!(matches!(
+                calculate_rhythmic(
+                    &events,
+                    bpm,
+                    &Tuning::bass_4(),
+                    &AlgorithmWeights::balanced()
+                ),
+                Err(SidFretError::InvalidInput(_))
+            ));
+        }
+    }
+}
@@ -1,8 +1,33 @@
-use super::position::FingeringPattern;
+use super::group::PositionGroup;
+use super::position::{FingeringPattern, FretPosition};
+use super::reach::HandSpan;
+use super::technique::Technique;
 use serde::{Deserialize, Serialize};
 
+/// 運指パターンのスコア内訳（各指標の生の値）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    pub total_movement: u32,
+    pub position_changes: u32,
+    pub open_string_count: u32,
+    pub string_changes: u32,
+}
+
+impl ScoreBreakdown {
+    pub fn from_pattern(pattern: &FingeringPattern) -> Self {
+        Self {
+            total_movement: pattern.total_movement(),
+            position_changes: pattern.position_changes(),
+            open_string_count: pattern.open_string_count(),
+            string_changes: pattern.string_changes(),
+        }
+    }
+}
+
 /// 運指アルゴリズムの重み設定
+/// デシリアライズ時に省略したフィールドはバランス型の値になる
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AlgorithmWeights {
     /// 移動距離の重み（小さいほど良い）
     pub movement_weight: f32,
@@ -12,8 +37,17 @@ pub struct AlgorithmWeights {
     pub open_string_weight: f32,
     /// 弦移動の重み（小さいほど弦移動を避ける）
     pub string_change_weight: f32,
+    /// 物理的な到達範囲（指定時は移動距離を実寸で評価し、届かない組み合わせを除外）
+    pub hand_span: Option<HandSpan>,
+    /// 基準となる音の間隔（秒）。これより短い間隔での移動・ポジション変更は割高になる
+    pub reference_interval: f32,
+    /// 奏法（ハンマリング・プリング・スライド）でつなげる移動のコストの割引率（0.0〜1.0）
+    pub technique_discount: f32,
 }
 
+/// 時間係数の計算に使う最短間隔（秒）。同時発音などで係数が発散しないようにする
+const MIN_INTERVAL: f32 = 0.02;
+
 impl Default for AlgorithmWeights {
     fn default() -> Self {
         Self {
@@ -21,6 +55,9 @@ impl Default for AlgorithmWeights {
             position_change_weight: 2.0,
             open_string_weight: -1.0, // マイナスは良いことを示す
             string_change_weight: 0.5,
+            hand_span: None,
+            reference_interval: 0.5, // 120bpmの4分音符
+            technique_discount: 0.0,
         }
     }
 }
@@ -33,6 +70,7 @@ impl AlgorithmWeights {
             position_change_weight: 1.0,
             open_string_weight: 0.0,
             string_change_weight: 0.5,
+            ..Self::default()
         }
     }
 
@@ -43,6 +81,7 @@ impl AlgorithmWeights {
             position_change_weight: 10.0,
             open_string_weight: 0.0,
             string_change_weight: 0.5,
+            ..Self::default()
         }
     }
 
@@ -53,6 +92,7 @@ impl AlgorithmWeights {
             position_change_weight: 2.0,
             open_string_weight: -5.0, // 大きくマイナス = 強く優先
             string_change_weight: 0.3,
+            ..Self::default()
         }
     }
 
@@ -63,6 +103,7 @@ impl AlgorithmWeights {
             position_change_weight: 1.0,
             open_string_weight: 0.0,
             string_change_weight: -1.0, // 弦移動を優先
+            ..Self::default()
         }
     }
 
@@ -71,21 +112,176 @@ impl AlgorithmWeights {
         Self::default()
     }
 
+    /// レガート奏法活用の重み設定（同じ弦上のシフトをスライドでつなぐ）
+    pub fn legato() -> Self {
+        Self {
+            technique_discount: 0.5,
+            ..Self::default()
+        }
+    }
+
+    /// 奏法による割引率を設定
+    pub fn with_technique_discount(mut self, technique_discount: f32) -> Self {
+        self.technique_discount = technique_discount;
+        self
+    }
+
+    /// 物理的な到達範囲を設定
+    pub fn with_hand_span(mut self, hand_span: HandSpan) -> Self {
+        self.hand_span = Some(hand_span);
+        self
+    }
+
+    /// 移動距離（hand_span指定時は実寸、それ以外はフレット数）
+    fn movement(&self, prev: &FretPosition, curr: &FretPosition) -> f32 {
+        match &self.hand_span {
+            Some(span) => span.movement(prev, curr),
+            None => prev.movement_to(curr) as f32,
+        }
+    }
+
+    /// 1音単体のコスト（開放弦の使用）
+    pub fn position_cost(&self, pos: &FretPosition) -> f32 {
+        if pos.fret == 0 {
+            self.open_string_weight
+        } else {
+            0.0
+        }
+    }
+
+    /// 移動に使える時間（秒）に対するコストの倍率（基準間隔で1.0、短いほど大きい）
+    pub fn time_factor(&self, seconds: f32) -> f32 {
+        self.reference_interval / seconds.max(MIN_INTERVAL)
+    }
+
+    /// 2音間の遷移コスト（移動距離・ポジション変更・弦移動）
+    /// 手の届く範囲は経路全体の手のポジションで判定するため（HandSpan::is_playable）、ここでは見ない
+    pub fn transition_cost(&self, prev: &FretPosition, curr: &FretPosition) -> f32 {
+        self.scaled_transition_cost(prev, curr, 1.0)
+    }
+
+    /// 移動に使える時間を考慮した遷移コスト
+    /// 移動距離とポジション変更のコストに time_factor を掛ける
+    pub fn timed_transition_cost(
+        &self,
+        prev: &FretPosition,
+        curr: &FretPosition,
+        seconds: f32,
+    ) -> f32 {
+        self.scaled_transition_cost(prev, curr, self.time_factor(seconds))
+    }
+
+    fn scaled_transition_cost(&self, prev: &FretPosition, curr: &FretPosition, factor: f32) -> f32 {
+        let movement_score = self.movement(prev, curr) * self.movement_weight;
+        let position_score = if prev.is_position_change(curr) {
+            self.position_change_weight
+        } else {
+            0.0
+        };
+        let string_change_score = if prev.string != curr.string {
+            self.string_change_weight
+        } else {
+            0.0
+        };
+
+        (movement_score + position_score) * self.technique_factor(prev, curr) * factor
+            + string_change_score
+    }
+
+    /// 奏法でつなげる移動なら割引後の倍率、それ以外は1.0
+    fn technique_factor(&self, prev: &FretPosition, curr: &FretPosition) -> f32 {
+        if Technique::legato_between(prev, curr).is_some() {
+            1.0 - self.technique_discount
+        } else {
+            1.0
+        }
+    }
+
+    /// 基準ポジション（人差し指のフレット）に手を置いた状態から最初の音へのコスト
+    pub fn entry_cost(&self, base_position: u8, curr: &FretPosition) -> f32 {
+        self.transition_cost(&FretPosition::new(curr.string, base_position), curr)
+    }
+
+    /// 同時に鳴らす音の形のコスト（開放弦の使用と押弦の幅）
+    pub fn group_cost(&self, group: &PositionGroup) -> f32 {
+        let open_score: f32 = group.positions.iter().map(|p| self.position_cost(p)).sum();
+        open_score + group.fret_span() as f32 * self.movement_weight
+    }
+
+    /// 形から形への遷移コスト（基準となる音同士の遷移、単音なら transition_cost と同じ）
+    pub fn group_transition_cost(&self, prev: &PositionGroup, curr: &PositionGroup) -> f32 {
+        match (prev.reference_position(), curr.reference_position()) {
+            (Some(prev), Some(curr)) => self.transition_cost(&prev, &curr),
+            _ => 0.0,
+        }
+    }
+
     /// 運指パターンのスコアを計算（低いほど良い）
+    /// 手の届く経路なら position_cost と transition_cost の総和に等しく、届かなければ無限大
     pub fn calculate_score(&self, pattern: &FingeringPattern) -> f32 {
-        let movement_score = pattern.total_movement() as f32 * self.movement_weight;
+        let movement = match &self.hand_span {
+            Some(span) if !span.is_playable(pattern) => return f32::INFINITY,
+            Some(span) => span.total_movement(pattern),
+            None => pattern.total_movement() as f32,
+        };
+        let movement_score = movement * self.movement_weight;
         let position_score = pattern.position_changes() as f32 * self.position_change_weight;
         let open_string_score = pattern.open_string_count() as f32 * self.open_string_weight;
         let string_change_score = pattern.string_changes() as f32 * self.string_change_weight;
 
         movement_score + position_score + open_string_score + string_change_score
+            - self.technique_savings(pattern)
+    }
+
+    /// 奏法でつなげる移動の割引額の合計
+    fn technique_savings(&self, pattern: &FingeringPattern) -> f32 {
+        pattern
+            .positions
+            .windows(2)
+            .map(|pair| {
+                let (prev, curr) = (&pair[0], &pair[1]);
+                let position_score = if prev.is_position_change(curr) {
+                    self.position_change_weight
+                } else {
+                    0.0
+                };
+                (self.movement(prev, curr) * self.movement_weight + position_score)
+                    * (1.0 - self.technique_factor(prev, curr))
+            })
+            .sum()
+    }
+
+    /// 音の間隔を考慮したスコアを計算（低いほど良い）
+    /// intervals[i] は i 番目の音から次の音までの時間（秒）。足りない分は基準間隔として扱う
+    pub fn calculate_timed_score(&self, pattern: &FingeringPattern, intervals: &[f32]) -> f32 {
+        if self
+            .hand_span
+            .is_some_and(|span| !span.is_playable(pattern))
+        {
+            return f32::INFINITY;
+        }
+        let unary: f32 = pattern
+            .positions
+            .iter()
+            .map(|p| self.position_cost(p))
+            .sum();
+        let pairwise: f32 = pattern
+            .positions
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| match intervals.get(i) {
+                Some(&seconds) => self.timed_transition_cost(&pair[0], &pair[1], seconds),
+                None => self.transition_cost(&pair[0], &pair[1]),
+            })
+            .sum();
+
+        unary + pairwise
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::instrument::fingering::position::FretPosition;
 
     #[test]
     fn test_weights_shortest() {
@@ -131,4 +327,121 @@ mod tests {
         // pattern1の方がスコアが低い（良い）はず
         assert!(score1 < score2);
     }
+
+    #[test]
+    fn test_score_decomposition() {
+        let pattern = FingeringPattern::new(
+            vec![
+                FretPosition::new(4, 0),
+                FretPosition::new(4, 3),
+                FretPosition::new(3, 7),
+                FretPosition::new(2, 0),
+                FretPosition::new(1, 9),
+            ],
+            "test".to_string(),
+        );
+
+        for weights in [
+            AlgorithmWeights::shortest(),
+            AlgorithmWeights::position_stable(),
+            AlgorithmWeights::open_string(),
+            AlgorithmWeights::string_priority(),
+            AlgorithmWeights::balanced(),
+            AlgorithmWeights::legato(),
+        ] {
+            let unary: f32 = pattern
+                .positions
+                .iter()
+                .map(|p| weights.position_cost(p))
+                .sum();
+            let pairwise: f32 = pattern
+                .positions
+                .windows(2)
+                .map(|pair| weights.transition_cost(&pair[0], &pair[1]))
+                .sum();
+            assert!((unary + pairwise - weights.calculate_score(&pattern)).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_calculate_score_with_hand_span() {
+        let weights = AlgorithmWeights::balanced().with_hand_span(HandSpan::long_scale());
+
+        // 同じ5フレットの移動でもローポジションの方がコストが高い
+        let low = FingeringPattern::new(
+            vec![FretPosition::new(4, 1), FretPosition::new(4, 6)],
+            "test".to_string(),
+        );
+        let high = FingeringPattern::new(
+            vec![FretPosition::new(4, 12), FretPosition::new(4, 17)],
+            "test".to_string(),
+        );
+        assert!(weights.calculate_score(&low) > weights.calculate_score(&high));
+
+        // 届かない組み合わせは無限大
+        let narrow = AlgorithmWeights::balanced().with_hand_span(HandSpan::new(864.0, 100.0));
+        let stretch = FingeringPattern::new(
+            vec![FretPosition::new(4, 1), FretPosition::new(3, 4)],
+            "test".to_string(),
+        );
+        assert!(narrow.calculate_score(&stretch).is_infinite());
+        assert!(narrow.calculate_timed_score(&stretch, &[0.5]).is_infinite());
+        // 届くかどうかは経路全体で判定し、遷移コストには含めない
+        assert!(narrow
+            .transition_cost(&stretch.positions[0], &stretch.positions[1])
+            .is_finite());
+    }
+
+    #[test]
+    fn test_weights_deserialize_without_hand_span() {
+        let json = r#"{"movement_weight":1.0,"position_change_weight":2.0,"open_string_weight":-1.0,"string_change_weight":0.5}"#;
+        let weights: AlgorithmWeights = serde_json::from_str(json).unwrap();
+        assert!(weights.hand_span.is_none());
+
+        // 一部だけ指定した場合は残りがバランス型の値
+        let json =
+            r#"{"open_string_weight":-3.0,"hand_span":{"scale_length":762.0,"max_span":120.0}}"#;
+        let weights: AlgorithmWeights = serde_json::from_str(json).unwrap();
+        assert_eq!(weights.open_string_weight, -3.0);
+        assert_eq!(
+            weights.movement_weight,
+            AlgorithmWeights::balanced().movement_weight
+        );
+        assert_eq!(weights.hand_span, Some(HandSpan::new(762.0, 120.0)));
+    }
+
+    #[test]
+    fn test_timed_score() {
+        let weights = AlgorithmWeights::balanced();
+        let shift = FingeringPattern::new(
+            vec![FretPosition::new(4, 1), FretPosition::new(4, 8)],
+            "test".to_string(),
+        );
+        let untimed = weights.calculate_score(&shift);
+
+        // 基準間隔ならスコアは変わらない
+        assert!((weights.calculate_timed_score(&shift, &[0.5]) - untimed).abs() < 1e-4);
+        assert!((weights.calculate_timed_score(&shift, &[]) - untimed).abs() < 1e-4);
+
+        // 短い間隔ほど割高、長い間隔ほど割安
+        let fast = weights.calculate_timed_score(&shift, &[0.1]);
+        let slow = weights.calculate_timed_score(&shift, &[2.0]);
+        assert!((fast - untimed * 5.0).abs() < 1e-3);
+        assert!(slow < untimed && untimed < fast);
+
+        // 同時発音でも有限
+        assert!(weights.calculate_timed_score(&shift, &[0.0]).is_finite());
+    }
+
+    #[test]
+    fn test_entry_cost() {
+        let weights = AlgorithmWeights::balanced();
+        // 基準ポジション上の音はコスト0
+        assert_eq!(weights.entry_cost(5, &FretPosition::new(3, 5)), 0.0);
+        // 遠いほど高い
+        assert!(
+            weights.entry_cost(5, &FretPosition::new(3, 12))
+                > weights.entry_cost(5, &FretPosition::new(3, 7))
+        );
+    }
 }
@@ -0,0 +1,220 @@
+use super::position::FretPosition;
+use super::technique::Technique;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// ポジション移動の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PositionShift {
+    Slide,       // スライドで移動（音をつなげたまま）
+    Jump,        // 手を離して移動
+    GuideFinger, // 同じ指で同じ弦をなぞって移動（ガイドフィンガー）
+}
+
+impl FromStr for PositionShift {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "slide" => Ok(Self::Slide),
+            "jump" => Ok(Self::Jump),
+            "guide-finger" | "guide" => Ok(Self::GuideFinger),
+            _ => Err(()),
+        }
+    }
+}
+
+impl PositionShift {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::Slide => "slide",
+            Self::Jump => "jump",
+            Self::GuideFinger => "guide-finger",
+        }
+    }
+
+    /// 前のポジションの最後の押弦音から次のポジションの最初の押弦音への移動を分類
+    /// 指番号が未割り当ての場合、同じ弦上の移動はガイドフィンガーとみなす
+    pub fn between(prev: &FretPosition, next: &FretPosition) -> Self {
+        if matches!(
+            next.technique,
+            Some(Technique::SlideUp | Technique::SlideDown)
+        ) {
+            Self::Slide
+        } else if prev.string == next.string && prev.finger == next.finger {
+            Self::GuideFinger
+        } else {
+            Self::Jump
+        }
+    }
+}
+
+/// 同じポジションで弾く音の区間
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionSegment {
+    /// 手のポジション（FretPosition::position、開放弦のみの区間は 0）
+    pub position: u8,
+    /// 区間の最初の音の位置
+    pub start: usize,
+    /// 区間の最後の音の次の位置（start..end）
+    pub end: usize,
+    /// この区間へ移るときの移動の種類（最初の区間は None）
+    pub shift: Option<PositionShift>,
+    /// 譜面に書くポジション記号（例: "V"、開放弦のみの区間は None）
+    pub marker: Option<String>,
+}
+
+impl PositionSegment {
+    fn new(position: u8, start: usize, shift: Option<PositionShift>) -> Self {
+        Self {
+            position,
+            start,
+            end: start,
+            shift,
+            marker: position_marker(position),
+        }
+    }
+
+    /// 区間に含まれる音数
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// ポジション番号をローマ数字の記号に変換（0 = 開放弦は None）
+pub fn position_marker(position: u8) -> Option<String> {
+    const NUMERALS: [(u8, &str); 7] = [
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    if position == 0 {
+        return None;
+    }
+    let mut rest = position;
+    let mut marker = String::new();
+    for &(value, numeral) in &NUMERALS {
+        while rest >= value {
+            marker.push_str(numeral);
+            rest -= value;
+        }
+    }
+    Some(marker)
+}
+
+/// 運指をポジションごとの区間に分ける
+/// 開放弦はどのポジションからも弾けるので直前の区間（先頭なら最初の押弦音の区間）に含める
+pub fn position_segments(positions: &[FretPosition]) -> Vec<PositionSegment> {
+    let mut segments: Vec<PositionSegment> = Vec::new();
+    let mut prev: Option<&FretPosition> = None;
+
+    for (i, pos) in positions.iter().enumerate() {
+        if pos.fret == 0 {
+            continue;
+        }
+        match prev {
+            None => segments.push(PositionSegment::new(pos.position(), 0, None)),
+            Some(p) if p.is_position_change(pos) => {
+                if let Some(last) = segments.last_mut() {
+                    last.end = i;
+                }
+                segments.push(PositionSegment::new(
+                    pos.position(),
+                    i,
+                    Some(PositionShift::between(p, pos)),
+                ));
+            }
+            Some(_) => {}
+        }
+        prev = Some(pos);
+    }
+
+    if segments.is_empty() && !positions.is_empty() {
+        segments.push(PositionSegment::new(0, 0, None));
+    }
+    if let Some(last) = segments.last_mut() {
+        last.end = positions.len();
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_marker() {
+        assert_eq!(position_marker(0), None);
+        assert_eq!(position_marker(1).as_deref(), Some("I"));
+        assert_eq!(position_marker(5).as_deref(), Some("V"));
+        assert_eq!(position_marker(7).as_deref(), Some("VII"));
+        assert_eq!(position_marker(9).as_deref(), Some("IX"));
+        assert_eq!(position_marker(13).as_deref(), Some("XIII"));
+        assert_eq!(position_marker(21).as_deref(), Some("XXI"));
+    }
+
+    #[test]
+    fn test_position_segments() {
+        let positions = vec![
+            FretPosition::new(3, 0), // 開放弦は最初の押弦音の区間に含める
+            FretPosition::new(4, 3),
+            FretPosition::new(3, 2),
+            FretPosition::new(2, 0), // 開放弦は直前の区間に含める
+            FretPosition::new(2, 7), // 別の弦で 5th ポジションへ
+            FretPosition::new(2, 10).with_technique(Technique::SlideUp), // 9th へスライド
+            FretPosition::new(2, 13), // 同じ弦で 13th へ
+        ];
+        let segments = position_segments(&positions);
+
+        assert_eq!(segments.len(), 4);
+        assert_eq!(
+            segments[0],
+            PositionSegment {
+                position: 1,
+                start: 0,
+                end: 4,
+                shift: None,
+                marker: Some("I".to_string()),
+            }
+        );
+        assert_eq!((segments[1].start, segments[1].end), (4, 5));
+        assert_eq!(segments[1].shift, Some(PositionShift::Jump));
+        assert_eq!(segments[1].marker.as_deref(), Some("V"));
+        assert_eq!(segments[2].shift, Some(PositionShift::Slide));
+        assert_eq!(segments[3].shift, Some(PositionShift::GuideFinger));
+        assert_eq!(segments[3].len(), 1);
+        assert_eq!(segments.last().map(|s| s.end), Some(positions.len()));
+    }
+
+    #[test]
+    fn test_guide_finger_needs_same_finger() {
+        let prev = FretPosition::new(3, 3).with_finger(2);
+        assert_eq!(
+            PositionShift::between(&prev, &FretPosition::new(3, 7).with_finger(2)),
+            PositionShift::GuideFinger
+        );
+        assert_eq!(
+            PositionShift::between(&prev, &FretPosition::new(3, 7).with_finger(1)),
+            PositionShift::Jump
+        );
+    }
+
+    #[test]
+    fn test_position_segments_edge_cases() {
+        assert!(position_segments(&[]).is_empty());
+
+        let open = position_segments(&[FretPosition::new(4, 0), FretPosition::new(3, 0)]);
+        assert_eq!(open.len(), 1);
+        assert_eq!((open[0].position, open[0].end), (0, 2));
+        assert_eq!(open[0].marker, None);
+    }
+}
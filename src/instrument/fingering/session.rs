@@ -0,0 +1,340 @@
+use super::algorithm::{generate_all_positions, parse_mode, parse_tuning};
+use super::optimal::{
+    first_column, held_positions, next_column, trace_paths, HeldPosition, PathColumn,
+};
+use super::position::{FingeringPattern, FretPosition};
+use super::scoring::AlgorithmWeights;
+use crate::error::{to_js, SidFretError};
+use crate::instrument::tuning::Tuning;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// 編集によってポジションが変わった音
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionChange {
+    /// 編集後の音の位置
+    pub index: usize,
+    pub position: FretPosition,
+}
+
+/// 1音ずつ入力するための運指セッション
+/// 動的計画法の表を保持し、編集された音以降だけを計算し直す（結果は calculate_optimal と一致する）
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct FingeringSession {
+    tuning: Tuning,
+    weights: AlgorithmWeights,
+    pitches: Vec<i32>,
+    candidates: Vec<Vec<FretPosition>>,
+    states: Vec<Vec<HeldPosition>>,
+    table: Vec<PathColumn>,
+    positions: Vec<FretPosition>,
+}
+
+impl FingeringSession {
+    pub fn new(tuning: Tuning, weights: AlgorithmWeights) -> Self {
+        Self {
+            tuning,
+            weights,
+            pitches: vec![],
+            candidates: vec![],
+            states: vec![],
+            table: vec![],
+            positions: vec![],
+        }
+    }
+
+    /// 入力済みの音（E1=0基準の半音値）
+    pub fn pitches(&self) -> &[i32] {
+        &self.pitches
+    }
+
+    /// 現在の最適運指
+    pub fn positions(&self) -> &[FretPosition] {
+        &self.positions
+    }
+
+    pub fn len(&self) -> usize {
+        self.pitches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pitches.is_empty()
+    }
+
+    /// 現在の運指パターン
+    pub fn pattern(&self) -> FingeringPattern {
+        let mut pattern = FingeringPattern::new(self.positions.clone(), "optimal".to_string());
+        pattern.score = self.weights.calculate_score(&pattern);
+        pattern
+    }
+
+    /// 末尾に音を追加し、ポジションが変わった音を返す
+    pub fn push(&mut self, pitch: i32) -> Result<Vec<PositionChange>, SidFretError> {
+        self.insert(self.pitches.len(), pitch)
+    }
+
+    /// index の位置に音を挿入し、ポジションが変わった音（挿入した音を含む）を返す
+    /// 失敗した場合はセッションを変更しない
+    pub fn insert(
+        &mut self,
+        index: usize,
+        pitch: i32,
+    ) -> Result<Vec<PositionChange>, SidFretError> {
+        if index > self.pitches.len() {
+            return Err(out_of_bounds(index, self.pitches.len()));
+        }
+        let positions = generate_all_positions(pitch, &self.tuning);
+        if positions.is_empty() {
+            return Err(SidFretError::PitchOutOfRange { index, pitch });
+        }
+
+        self.pitches.insert(index, pitch);
+        self.candidates.insert(index, positions);
+        self.update(index, |i| match i.cmp(&index) {
+            std::cmp::Ordering::Less => Some(i),
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some(i - 1),
+        })
+        .inspect_err(|_| {
+            self.pitches.remove(index);
+            self.candidates.remove(index);
+        })
+    }
+
+    /// index の音を削除し、ポジションが変わった音（削除後の位置）を返す
+    /// 失敗した場合はセッションを変更しない
+    pub fn remove(&mut self, index: usize) -> Result<Vec<PositionChange>, SidFretError> {
+        if index >= self.pitches.len() {
+            return Err(out_of_bounds(index, self.pitches.len()));
+        }
+
+        let pitch = self.pitches.remove(index);
+        let positions = self.candidates.remove(index);
+        self.update(index, |i| Some(if i < index { i } else { i + 1 }))
+            .inspect_err(|_| {
+                self.pitches.insert(index, pitch);
+                self.candidates.insert(index, positions);
+            })
+    }
+
+    /// start 番目以降の表を計算し直して最適運指を更新する
+    /// old_index は編集後の位置から編集前の位置への対応（新しい音は None）
+    fn update(
+        &mut self,
+        start: usize,
+        old_index: impl Fn(usize) -> Option<usize>,
+    ) -> Result<Vec<PositionChange>, SidFretError> {
+        let weights = &self.weights;
+        let hand_span = weights.hand_span.as_ref();
+        let mut states: Vec<Vec<HeldPosition>> = Vec::with_capacity(self.candidates.len() - start);
+        let mut columns: Vec<PathColumn> = Vec::with_capacity(self.candidates.len() - start);
+        for i in start..self.candidates.len() {
+            let prev_states = match i.checked_sub(1) {
+                Some(prev) if prev >= start => Some(states[prev - start].as_slice()),
+                Some(prev) => Some(self.states[prev].as_slice()),
+                None => None,
+            };
+            let curr_states = held_positions(prev_states, &self.candidates[i], hand_span);
+            let column = match prev_states {
+                None => first_column(&curr_states, |s| weights.position_cost(&s.position)),
+                Some(prev_states) => {
+                    let prev_column = if i > start {
+                        &columns[i - 1 - start]
+                    } else {
+                        &self.table[i - 1]
+                    };
+                    next_column(
+                        prev_states,
+                        prev_column,
+                        &curr_states,
+                        1,
+                        |s| weights.position_cost(&s.position),
+                        |prev, curr| {
+                            if curr.follows(prev, hand_span) {
+                                weights.transition_cost(&prev.position, &curr.position)
+                            } else {
+                                f32::INFINITY
+                            }
+                        },
+                    )
+                }
+            };
+            // 手の届く経路が途切れた
+            if column.iter().all(Vec::is_empty) {
+                return Err(SidFretError::InfeasibleConstraint { index: i });
+            }
+            states.push(curr_states);
+            columns.push(column);
+        }
+
+        self.states.truncate(start);
+        self.states.extend(states);
+        self.table.truncate(start);
+        self.table.extend(columns);
+        let positions: Vec<FretPosition> = trace_paths(&self.states, &self.table, 1)
+            .into_iter()
+            .next()
+            .map(|(path, _)| path.iter().map(|s| s.position).collect())
+            .unwrap_or_default();
+
+        let changes = positions
+            .iter()
+            .enumerate()
+            .filter(|&(i, pos)| old_index(i).is_none_or(|old| self.positions[old] != *pos))
+            .map(|(index, &position)| PositionChange { index, position })
+            .collect();
+        self.positions = positions;
+        Ok(changes)
+    }
+}
+
+fn out_of_bounds(index: usize, len: usize) -> SidFretError {
+    SidFretError::InvalidInput(format!(
+        "note index {index} is out of bounds for {len} notes"
+    ))
+}
+
+#[wasm_bindgen]
+impl FingeringSession {
+    /// WASM公開API: 運指モードとチューニングを指定してセッションを作成
+    #[wasm_bindgen(constructor)]
+    pub fn create(mode: &str, tuning_name: &str) -> Result<FingeringSession, JsValue> {
+        let fingering_mode = parse_mode(mode)?;
+        let tuning = parse_tuning(tuning_name)?;
+        Ok(Self::new(tuning, fingering_mode.weights()))
+    }
+
+    /// WASM公開API: 末尾に音を追加（`PositionChange[]` を返す）
+    #[wasm_bindgen(js_name = push)]
+    pub fn push_js(&mut self, pitch: i32) -> Result<JsValue, JsValue> {
+        let changes = self.push(pitch)?;
+        Ok(to_js(&changes)?)
+    }
+
+    /// WASM公開API: 音を挿入（`PositionChange[]` を返す）
+    #[wasm_bindgen(js_name = insert)]
+    pub fn insert_js(&mut self, index: usize, pitch: i32) -> Result<JsValue, JsValue> {
+        let changes = self.insert(index, pitch)?;
+        Ok(to_js(&changes)?)
+    }
+
+    /// WASM公開API: 音を削除（`PositionChange[]` を返す）
+    #[wasm_bindgen(js_name = remove)]
+    pub fn remove_js(&mut self, index: usize) -> Result<JsValue, JsValue> {
+        let changes = self.remove(index)?;
+        Ok(to_js(&changes)?)
+    }
+
+    /// WASM公開API: 現在の運指パターン
+    #[wasm_bindgen(js_name = pattern)]
+    pub fn pattern_js(&self) -> Result<JsValue, JsValue> {
+        Ok(to_js(&self.pattern())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instrument::fingering::optimal::calculate_optimal;
+    use crate::instrument::fingering::reach::HandSpan;
+
+    fn assert_matches_full(session: &FingeringSession) {
+        let full = calculate_optimal(session.pitches(), &session.tuning, &session.weights).unwrap();
+        assert_eq!(session.positions(), full.positions.as_slice());
+        assert_eq!(session.pattern().score, full.score);
+    }
+
+    #[test]
+    fn test_push_matches_full_optimization() {
+        let mut session = FingeringSession::new(Tuning::bass_4(), AlgorithmWeights::balanced());
+        for pitch in [0, 2, 4, 5, 7, 9, 11, 12, 14, 16, 17, 19, 21, 23, 24] {
+            let changes = session.push(pitch).unwrap();
+            // 追加した音は必ず含まれる
+            assert_eq!(changes.last().map(|c| c.index), Some(session.len() - 1));
+            assert_matches_full(&session);
+        }
+    }
+
+    #[test]
+    fn test_hand_window_matches_full_optimization() {
+        // 同じポジションで押さえた範囲を追跡した結果も一括計算と一致する
+        let weights = AlgorithmWeights::shortest().with_hand_span(HandSpan::long_scale());
+        let mut session = FingeringSession::new(Tuning::bass_4(), weights);
+        for pitch in [1, 4, 10, 3, 6] {
+            session.push(pitch).unwrap();
+            assert_matches_full(&session);
+        }
+        assert_eq!(session.positions()[2], FretPosition::new(2, 0));
+        session.remove(0).unwrap();
+        assert_matches_full(&session);
+        session.insert(0, 1).unwrap();
+        assert_matches_full(&session);
+    }
+
+    #[test]
+    fn test_insert_and_remove_match_full_optimization() {
+        let mut session = FingeringSession::new(Tuning::bass_5(), AlgorithmWeights::shortest());
+        for pitch in [-5, 0, 7, 12, 5] {
+            session.push(pitch).unwrap();
+        }
+        let edits: [(bool, usize, i32); 6] = [
+            (true, 0, 10),
+            (true, 3, 19),
+            (false, 1, 0),
+            (true, 5, -3),
+            (false, 0, 0),
+            (false, 4, 0),
+        ];
+        for (insert, index, pitch) in edits {
+            let before = session.positions().to_vec();
+            let changes = if insert {
+                session.insert(index, pitch).unwrap()
+            } else {
+                session.remove(index).unwrap()
+            };
+            assert_matches_full(&session);
+
+            // 変更として返した音以外は編集前と同じポジション
+            for (i, pos) in session.positions().iter().enumerate() {
+                if changes.iter().all(|c| c.index != i) {
+                    let old = match (insert, i < index) {
+                        (_, true) => i,
+                        (true, false) => i - 1,
+                        (false, false) => i + 1,
+                    };
+                    assert_eq!(*pos, before[old]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors_leave_session_unchanged() {
+        let weights = AlgorithmWeights::balanced().with_hand_span(HandSpan::new(864.0, 100.0));
+        let mut session = FingeringSession::new(Tuning::bass_4(), weights);
+        session.push(1).unwrap();
+
+        assert_eq!(
+            session.push(40).unwrap_err(),
+            SidFretError::PitchOutOfRange {
+                index: 1,
+                pitch: 40
+            }
+        );
+        // 手が小さいと F1 → G♯1 に届かない
+        assert_eq!(
+            session.push(4).unwrap_err(),
+            SidFretError::InfeasibleConstraint { index: 1 }
+        );
+        assert_eq!(session.remove(3).unwrap_err().code(), "INVALID_INPUT");
+        assert_eq!(session.pitches(), &[1]);
+
+        session.push(9).unwrap();
+        assert_matches_full(&session);
+        assert!(session.remove(0).is_ok());
+        assert_matches_full(&session);
+        assert!(session.remove(0).is_ok());
+        assert!(session.is_empty() && session.positions().is_empty());
+    }
+}
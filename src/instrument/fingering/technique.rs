@@ -0,0 +1,256 @@
+use super::algorithm::{parse_mode, parse_tuning};
+use super::optimal::calculate_optimal;
+use super::position::{FingeringPattern, FretPosition};
+use super::scoring::AlgorithmWeights;
+use crate::error::{to_js, SidFretError};
+use crate::instrument::tuning::Tuning;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+/// ハンマリング・プリングで届くフレット差の上限
+const MAX_LEGATO_FRETS: u8 = 3;
+
+/// 奏法（レガート奏法は前の音からの奏法、スラップ・プルは音自体の奏法）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Technique {
+    HammerOn,  // ハンマリング・オン
+    PullOff,   // プリング・オフ
+    SlideUp,   // スライド（上行）
+    SlideDown, // スライド（下行）
+    Slap,      // サムピング
+    Pop,       // プル（プラッキング）
+}
+
+impl FromStr for Technique {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hammer-on" | "h" => Ok(Self::HammerOn),
+            "pull-off" | "p" => Ok(Self::PullOff),
+            "slide-up" | "/" => Ok(Self::SlideUp),
+            "slide-down" | "\\" => Ok(Self::SlideDown),
+            "slap" | "T" => Ok(Self::Slap),
+            "pop" | "P" => Ok(Self::Pop),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Technique {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::HammerOn => "hammer-on",
+            Self::PullOff => "pull-off",
+            Self::SlideUp => "slide-up",
+            Self::SlideDown => "slide-down",
+            Self::Slap => "slap",
+            Self::Pop => "pop",
+        }
+    }
+
+    /// TAB譜の記号
+    pub fn tab_symbol(&self) -> &'static str {
+        match self {
+            Self::HammerOn => "h",
+            Self::PullOff => "p",
+            Self::SlideUp => "/",
+            Self::SlideDown => "\\",
+            Self::Slap => "T",
+            Self::Pop => "P",
+        }
+    }
+
+    /// 前の音から右手で弾き直さずにつなぐ奏法か
+    pub fn is_legato(&self) -> bool {
+        matches!(
+            self,
+            Self::HammerOn | Self::PullOff | Self::SlideUp | Self::SlideDown
+        )
+    }
+
+    /// 同じ弦上の2音をつなぐレガート奏法
+    /// 同じポジション内の近い音はハンマリング・プリング、押弦音どうしのシフトはスライド
+    pub fn legato_between(prev: &FretPosition, curr: &FretPosition) -> Option<Self> {
+        if prev.string != curr.string || prev.fret == curr.fret {
+            return None;
+        }
+        let ascending = curr.fret > prev.fret;
+        if !prev.is_position_change(curr) && prev.fret.abs_diff(curr.fret) <= MAX_LEGATO_FRETS {
+            Some(if ascending {
+                Self::HammerOn
+            } else {
+                Self::PullOff
+            })
+        } else if prev.fret != 0 && curr.fret != 0 {
+            Some(if ascending {
+                Self::SlideUp
+            } else {
+                Self::SlideDown
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// 1オクターブ上の音を2本以上高い弦で弾く組み合わせ（スラップ→プル）か
+fn is_octave_pop(prev: &FretPosition, curr: &FretPosition, tuning: &Tuning) -> bool {
+    curr.absolute_pitch(tuning) - prev.absolute_pitch(tuning) == 12
+        && prev.string >= curr.string + 2
+}
+
+/// 運指パターンに奏法を提案して付与する
+/// 既存の奏法は付け直す。オクターブのスラップ→プルは前の音に奏法がない場合のみスラップを付ける
+pub fn annotate_techniques(pattern: &FingeringPattern, tuning: &Tuning) -> FingeringPattern {
+    let mut positions: Vec<FretPosition> = pattern
+        .positions
+        .iter()
+        .map(|p| FretPosition {
+            technique: None,
+            ..*p
+        })
+        .collect();
+
+    for i in 1..positions.len() {
+        let (prev, curr) = (positions[i - 1], positions[i]);
+        if let Some(technique) = Technique::legato_between(&prev, &curr) {
+            positions[i].technique = Some(technique);
+        } else if is_octave_pop(&prev, &curr, tuning) {
+            positions[i].technique = Some(Technique::Pop);
+            if prev.technique.is_none() {
+                positions[i - 1].technique = Some(Technique::Slap);
+            }
+        }
+    }
+
+    FingeringPattern::new(positions, pattern.algorithm.clone()).with_score(pattern.score)
+}
+
+/// 奏法を考慮した最適運指（奏法でつなげる移動を割り引いて探索し、奏法を付与する）
+pub fn calculate_with_techniques(
+    pitches: &[i32],
+    tuning: &Tuning,
+    weights: &AlgorithmWeights,
+) -> Result<FingeringPattern, SidFretError> {
+    let pattern = calculate_optimal(pitches, tuning, weights)?;
+    Ok(annotate_techniques(&pattern, tuning))
+}
+
+/// WASM公開API: 奏法付きの運指計算（モードの重みに legato の割引率を適用）
+#[wasm_bindgen]
+pub fn calculate_fingering_with_techniques(
+    pitches: Vec<i32>,
+    mode: &str,
+    tuning_name: &str,
+) -> Result<JsValue, JsValue> {
+    let fingering_mode = parse_mode(mode)?;
+    let tuning = parse_tuning(tuning_name)?;
+    let weights = fingering_mode
+        .weights()
+        .with_technique_discount(AlgorithmWeights::legato().technique_discount);
+
+    let pattern = calculate_with_techniques(&pitches, &tuning, &weights)?;
+    Ok(to_js(&pattern)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn techniques(pattern: &FingeringPattern) -> Vec<Option<Technique>> {
+        pattern.positions.iter().map(|p| p.technique).collect()
+    }
+
+    #[test]
+    fn test_legato_between() {
+        let at = FretPosition::new;
+        assert_eq!(
+            Technique::legato_between(&at(3, 5), &at(3, 7)),
+            Some(Technique::HammerOn)
+        );
+        assert_eq!(
+            Technique::legato_between(&at(3, 2), &at(3, 0)),
+            Some(Technique::PullOff)
+        );
+        assert_eq!(
+            Technique::legato_between(&at(3, 0), &at(3, 3)),
+            Some(Technique::HammerOn)
+        );
+        assert_eq!(
+            Technique::legato_between(&at(3, 5), &at(3, 12)),
+            Some(Technique::SlideUp)
+        );
+        assert_eq!(
+            Technique::legato_between(&at(3, 9), &at(3, 7)),
+            Some(Technique::SlideDown)
+        );
+        // 弦移動・同じフレット・開放弦からの遠い音は対象外
+        assert_eq!(Technique::legato_between(&at(3, 5), &at(2, 7)), None);
+        assert_eq!(Technique::legato_between(&at(3, 5), &at(3, 5)), None);
+        assert_eq!(Technique::legato_between(&at(3, 0), &at(3, 9)), None);
+    }
+
+    #[test]
+    fn test_annotate_techniques() {
+        let tuning = Tuning::bass_4();
+        let pattern = FingeringPattern::new(
+            vec![
+                FretPosition::new(4, 5),  // A1
+                FretPosition::new(4, 7),  // ハンマリング
+                FretPosition::new(4, 5),  // プリング
+                FretPosition::new(2, 7),  // A2: オクターブのプル
+                FretPosition::new(2, 12), // スライド
+            ],
+            "test".to_string(),
+        );
+        let result = annotate_techniques(&pattern, &tuning);
+        assert_eq!(
+            techniques(&result),
+            vec![
+                None,
+                Some(Technique::HammerOn),
+                Some(Technique::PullOff),
+                Some(Technique::Pop),
+                Some(Technique::SlideUp),
+            ]
+        );
+
+        // 前の音に奏法がなければスラップ→プル
+        let pattern = FingeringPattern::new(
+            vec![FretPosition::new(4, 3), FretPosition::new(2, 5)],
+            "test".to_string(),
+        );
+        assert_eq!(
+            techniques(&annotate_techniques(&pattern, &tuning)),
+            vec![Some(Technique::Slap), Some(Technique::Pop)]
+        );
+    }
+
+    #[test]
+    fn test_technique_discount_prefers_slide() {
+        let tuning = Tuning::bass_4();
+        // E1 → G＃1 → B1
+        let pitches = [0, 4, 7];
+        let plain = calculate_optimal(&pitches, &tuning, &AlgorithmWeights::balanced()).unwrap();
+        assert_eq!(plain.positions[2], FretPosition::new(3, 2));
+
+        // 割引があれば E弦上のスライドでつなぐ
+        let legato =
+            calculate_with_techniques(&pitches, &tuning, &AlgorithmWeights::legato()).unwrap();
+        assert_eq!(
+            legato.positions[2],
+            FretPosition::new(4, 7).with_technique(Technique::SlideUp)
+        );
+        assert!(legato.score < AlgorithmWeights::balanced().calculate_score(&legato));
+    }
+
+    #[test]
+    fn test_technique_from_str() {
+        assert_eq!("h".parse(), Ok(Technique::HammerOn));
+        assert_eq!("slide-down".parse(), Ok(Technique::SlideDown));
+        assert_eq!(Technique::Pop.tab_symbol(), "P");
+        assert!("tap".parse::<Technique>().is_err());
+    }
+}
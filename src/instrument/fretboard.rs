@@ -1,7 +1,8 @@
 //! フレットボード計算（楽器固有）
 
-use crate::core::chord_type::{chromatic_chord_tones, diatonic_chord_tones, get_chord_tones, get_root_note, parse_chord_type, ChordTone};
-use crate::core::pitch::{pitch_map_for_root, fret_offset, strip_octave};
+use crate::core::chord_symbol::ChordSymbol;
+use crate::core::chord_type::{chromatic_chord_tones, diatonic_chord_tones, get_root_note, ChordTone};
+use crate::core::pitch::{pitch_map_for_root, fret_offset, note_to_semitone, strip_octave};
 use crate::instrument::tuning::Tuning;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
@@ -94,6 +95,31 @@ fn convert_to_positions(frets: &[FretWithPitch], tuning: &Tuning) -> Vec<Positio
     positions
 }
 
+/// 分数コードのベース音を "/3" のように区別した構成音
+/// ベース音が構成音でない場合（C/D など）は "/2" として加える
+fn slash_chord_tones(symbol: &ChordSymbol) -> Vec<ChordTone> {
+    let mut tones = symbol.tones();
+    let (Some(bass), Some(degree)) = (symbol.bass, symbol.bass_degree()) else {
+        return tones;
+    };
+    let root = symbol.root.pitch_class();
+    match tones
+        .iter_mut()
+        .find(|t| root.transpose(t.semitones) == bass.pitch_class())
+    {
+        Some(tone) => tone.interval = format!("/{}", tone.interval),
+        None => {
+            let semitones = degree.semitones().rem_euclid(12);
+            let index = tones
+                .iter()
+                .position(|t| t.semitones > semitones)
+                .unwrap_or(tones.len());
+            tones.insert(index, ChordTone { interval: format!("/{degree}"), semitones });
+        }
+    }
+    tones
+}
+
 /// コード名とチューニングからフレットボードポジションを計算
 pub fn chord_positions(chord: &str, tuning: &Tuning) -> Vec<Position> {
     let is_all_keys = chord == "ALL_KEYS";
@@ -122,9 +148,11 @@ pub fn chord_positions(chord: &str, tuning: &Tuning) -> Vec<Position> {
         ];
         (tones, get_root_note(chord))
     } else {
-        let (root, chord_type) = parse_chord_type(chord);
-        let tones = get_chord_tones(&chord_type);
-        (tones, root)
+        // 解釈できないコードにはポジションがない
+        let Ok(symbol) = chord.parse::<ChordSymbol>() else {
+            return vec![];
+        };
+        (slash_chord_tones(&symbol), symbol.root.to_string())
     };
 
     let offset = fret_offset(&use_root);
@@ -166,18 +194,9 @@ pub fn chord_positions(chord: &str, tuning: &Tuning) -> Vec<Position> {
 
 /// インターバル記号を取得
 pub fn interval_for_pitch(chord: &str, target_pitch: &str) -> String {
-    let target_name = strip_octave(target_pitch);
-    let root = get_root_note(chord);
-    let pitches = pitch_map_for_root(&root);
-
-    let index = pitches
-        .iter()
-        .position(|pitch| {
-            pitch
-                .split('/')
-                .any(|p| strip_octave(p) == target_name)
-        })
-        .unwrap_or(0);
+    let root = note_to_semitone(&get_root_note(chord)).unwrap_or(0);
+    let index = note_to_semitone(&strip_octave(target_pitch))
+        .map_or(0, |target| (target - root).rem_euclid(12) as usize);
 
     let interval_map = [
         "1", "♭2", "2", "♭3", "3", "4", "＃4/♭5", "5", "＃5", "6", "♭7", "7",
@@ -333,6 +352,41 @@ mod tests {
         assert!(intervals.contains("7"));
     }
 
+    /// ダブルシャープ/ダブルフラットのルートは異名同音のコードと同じポジション
+    #[test]
+    fn test_spec_chord_positions_double_accidental_roots() {
+        let frets = |chord: &str| -> Vec<(i32, i32, String)> {
+            chord_positions(chord, &Tuning::bass_4()).into_iter().map(|p| (p.string, p.fret, p.interval)).collect()
+        };
+        assert!(!frets("F＃＃m").is_empty());
+        assert_eq!(frets("F＃＃m"), frets("Gm"));
+        assert_eq!(frets("B♭♭7"), frets("A7"));
+        assert_eq!(interval_for_pitch("E", "F＃＃1"), "♭3");
+        assert_eq!(interval_for_pitch("G＃m", "F＃＃2"), "7");
+    }
+
+    /// 分数コードのベース音は "/" 付きのインターバルで区別する
+    #[test]
+    fn test_spec_slash_chord_bass() {
+        let intervals = |chord: &str| -> std::collections::BTreeSet<String> {
+            chord_positions(chord, &Tuning::bass_4()).into_iter().map(|p| p.interval).collect()
+        };
+        let frets = |chord: &str| -> Vec<(i32, i32)> {
+            chord_positions(chord, &Tuning::bass_4()).into_iter().map(|p| (p.string, p.fret)).collect()
+        };
+
+        let c_e = chord_positions("C/E", &Tuning::bass_4());
+        assert!(c_e.iter().filter(|p| p.interval == "/3").all(|p| p.pitch.starts_with('E')));
+        assert_eq!(intervals("C/E"), ["/3", "1", "5"].map(String::from).into());
+        assert_eq!(frets("C/E"), frets("C"));
+        assert_eq!(intervals("Am7/G"), ["/♭7", "1", "5", "♭3"].map(String::from).into());
+
+        // 構成音でないベース音は加える
+        assert_eq!(intervals("C/D"), ["/2", "1", "3", "5"].map(String::from).into());
+        assert!(chord_positions("C/D", &Tuning::bass_4()).iter().any(|p| p.interval == "/2" && p.string == 3 && p.fret == 5));
+        assert!(chord_positions("C/Q", &Tuning::bass_4()).is_empty());
+    }
+
     /// 5弦は4弦より多い（または同数）ポジション
     #[test]
     fn test_spec_5string_more_positions() {
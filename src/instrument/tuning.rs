@@ -1,6 +1,10 @@
 //! チューニング定義
 
+use crate::core::frequency::{parse_reference, ReferencePitch};
+use crate::core::pitch::{Pitch, E1_ABSOLUTE_SEMITONE};
+use crate::error::{to_js, SidFretError};
 use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
 
 /// 弦の定義
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -9,6 +13,16 @@ pub struct StringDef {
     pub offset: i32,
 }
 
+/// 開放弦の音と周波数
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OpenString {
+    /// 弦番号（1=最高音弦）
+    pub string: u8,
+    pub pitch: Pitch,
+    /// 周波数（Hz）
+    pub frequency: f64,
+}
+
 /// チューニング定義
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Tuning {
@@ -77,6 +91,37 @@ impl Tuning {
         }
     }
 
+    /// 弦番号（1=最高音弦）から開放弦のオフセットを取得
+    pub fn string_offset(&self, string: u8) -> Option<i32> {
+        if string == 0 {
+            return None;
+        }
+        let index = self.strings.len().checked_sub(string as usize)?;
+        Some(self.strings[index].offset)
+    }
+
+    /// 開放弦のピッチ（低音弦から高音弦の順）
+    pub fn open_pitches(&self) -> Vec<Pitch> {
+        self.strings
+            .iter()
+            .map(|s| Pitch::from_semitone(E1_ABSOLUTE_SEMITONE + s.offset))
+            .collect()
+    }
+
+    /// 開放弦の周波数表（低音弦から高音弦の順）
+    pub fn open_strings(&self, reference: ReferencePitch) -> Vec<OpenString> {
+        let count = self.strings.len();
+        self.open_pitches()
+            .into_iter()
+            .enumerate()
+            .map(|(i, pitch)| OpenString {
+                string: (count - i) as u8,
+                pitch,
+                frequency: reference.frequency(pitch),
+            })
+            .collect()
+    }
+
     /// 名前からプリセットを取得
     pub fn from_name(name: &str) -> Option<Self> {
         match name {
@@ -89,6 +134,15 @@ impl Tuning {
     }
 }
 
+/// WASM公開API: 開放弦の周波数表（`OpenString[]`、a4 は基準ピッチ）
+#[wasm_bindgen]
+pub fn get_open_string_frequencies(tuning_name: &str, a4: f64) -> Result<JsValue, JsValue> {
+    let tuning = Tuning::from_name(tuning_name)
+        .ok_or_else(|| SidFretError::UnknownTuning(tuning_name.to_string()))?;
+    let reference = parse_reference(a4)?;
+    Ok(to_js(&tuning.open_strings(reference))?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,4 +173,40 @@ mod tests {
         assert!(Tuning::from_name("bass_drop_d").is_some());
         assert!(Tuning::from_name("unknown").is_none());
     }
+
+    #[test]
+    fn test_string_offset() {
+        let bass4 = Tuning::bass_4();
+        assert_eq!(bass4.string_offset(1), Some(15)); // G弦
+        assert_eq!(bass4.string_offset(4), Some(0)); // E弦
+        assert_eq!(bass4.string_offset(0), None);
+        assert_eq!(bass4.string_offset(5), None);
+
+        let bass5 = Tuning::bass_5();
+        assert_eq!(bass5.string_offset(5), Some(-5)); // B弦
+    }
+
+    #[test]
+    fn test_open_strings() {
+        let names = |tuning: Tuning| -> Vec<String> {
+            tuning.open_pitches().iter().map(Pitch::to_string).collect()
+        };
+        assert_eq!(names(Tuning::bass_4()), ["E1", "A1", "D2", "G2"]);
+        assert_eq!(
+            names(Tuning::bass_6()),
+            ["B0", "E1", "A1", "D2", "G2", "C3"]
+        );
+        assert_eq!(names(Tuning::bass_drop_d()), ["D1", "A1", "D2", "G2"]);
+
+        let strings = Tuning::bass_5().open_strings(ReferencePitch::STANDARD);
+        assert_eq!(strings.len(), 5);
+        assert_eq!(strings[0].string, 5); // B弦
+        assert!((strings[0].frequency - 30.87).abs() < 0.01);
+        assert_eq!(strings[4].string, 1); // G弦
+        assert!((strings[4].frequency - 98.0).abs() < 0.01);
+
+        // A1 は A4 の 1/8
+        let a442 = Tuning::bass_4().open_strings(ReferencePitch::new(442.0).unwrap());
+        assert!((a442[1].frequency - 55.25).abs() < 1e-9);
+    }
 }
@@ -1,6 +1,8 @@
 use wasm_bindgen::prelude::*;
 
+pub mod audio;
 pub mod core;
+pub mod error;
 pub mod harmony;
 pub mod instrument;
 pub mod utils;
@@ -11,11 +11,9 @@ pub fn get_chord_name_aliases(chord: &str) -> Vec<JsValue> {
 
 /// 内部用のalias取得関数
 fn get_chord_name_aliases_internal(chord: &str) -> Vec<String> {
-    let root = crate::core::chord_type::get_root_note(chord);
-    if root.is_empty() {
+    let Some((root, type_part)) = crate::core::chord_type::split_root(chord) else {
         return vec![chord.to_string()];
-    }
-    let type_part = &chord[root.len()..];
+    };
 
     // 代表的なコードタイプの別表記マップ
     let type_alias_map = create_type_alias_map();
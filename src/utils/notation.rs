@@ -1,43 +1,36 @@
 use wasm_bindgen::prelude::*;
 
-use crate::core::pitch::parse_pitch;
+use crate::core::pitch::Pitch;
 
 /// 五線譜のライン番号を計算（E1=0.0基準）
-/// ダイアトニック位置 + オクターブ×7 からE1のオフセットを引く
-/// シャープは+0.5、フラットは-0.5（E＃/B＃/F♭/C♭は異名同音扱い）
+/// 実際の音の高さを幹音の位置に置き、幹音の間の音（シャープ・フラット）は+0.5
+/// E＃/B＃/F♭/C♭ やダブルシャープ・ダブルフラットは異名同音の幹音の位置（F＃＃ → G）
 #[wasm_bindgen]
 pub fn get_line(pitch: &str) -> Option<f32> {
-    let (note, octave) = parse_pitch(pitch)?;
-
-    let base = note.chars().next()?;
-    let accidental = &note[base.len_utf8()..];
-
-    // ダイアトニック位置: C=0, D=1, E=2, F=3, G=4, A=5, B=6
-    let diatonic = match base {
-        'C' => 0,
-        'D' => 1,
-        'E' => 2,
-        'F' => 3,
-        'G' => 4,
-        'A' => 5,
-        'B' => 6,
-        _ => return None,
-    };
-
-    // E＃→F位置、B＃→C(次オクターブ)位置、F♭→E位置、C♭→B(前オクターブ)位置
-    let (d, oct_adj, acc) = match (base, accidental) {
-        ('E', "＃") => (3, 0, 0.0),
-        ('B', "＃") => (0, 1, 0.0),
-        ('F', "♭") => (2, 0, 0.0),
-        ('C', "♭") => (6, -1, 0.0),
-        (_, "＃") => (diatonic, 0, 0.5),
-        (_, "♭") => (diatonic, 0, -0.5),
-        (_, "") => (diatonic, 0, 0.0),
-        _ => return None,
-    };
+    let pitch: Pitch = pitch.parse().ok()?;
+
+    // 実際の高さ（オクターブは幹音に付くので B＃1 = C2、C♭2 = B1）
+    let height = pitch.semitone();
+
+    // 半音ごとのダイアトニック位置（C=0, D=1, E=2, F=3, G=4, A=5, B=6）と幹音の間か
+    const DIATONIC: [(i32, f32); 12] = [
+        (0, 0.0),
+        (0, 0.5),
+        (1, 0.0),
+        (1, 0.5),
+        (2, 0.0),
+        (3, 0.0),
+        (3, 0.5),
+        (4, 0.0),
+        (4, 0.5),
+        (5, 0.0),
+        (5, 0.5),
+        (6, 0.0),
+    ];
+    let (d, acc) = DIATONIC[height.rem_euclid(12) as usize];
 
     // E1 = 0.0 (オフセット = 1*7 + 2 = 9)
-    let line = (octave + oct_adj) as f32 * 7.0 + d as f32 + acc - 9.0;
+    let line = height.div_euclid(12) as f32 * 7.0 + d as f32 + acc - 9.0;
 
     // E1 (0.0) 〜 G4 (23.0) の範囲
     if (0.0..=23.0).contains(&line) {
@@ -151,6 +144,7 @@ mod tests {
         assert_eq!(get_line("C2"), Some(5.0));
         assert_eq!(get_line("G4"), Some(23.0));
         assert_eq!(get_line("X1"), None);
+        assert_eq!(get_line("C300000000"), None);
 
         // シャープ/フラット
         assert_eq!(get_line("F＃1"), Some(1.5));
@@ -224,4 +218,15 @@ mod tests {
         assert_eq!(get_line("D1"), None);
         assert_eq!(get_line("A4"), None);
     }
+
+    /// ダブルシャープ/ダブルフラットは異名同音の幹音の位置
+    #[test]
+    fn test_spec_get_line_double_accidentals() {
+        assert_eq!(get_line("F＃＃1"), Some(2.0)); // = G1
+        assert_eq!(get_line("B♭♭1"), Some(3.0)); // = A1
+        assert_eq!(get_line("C＃＃2"), Some(6.0)); // = D2
+        assert_eq!(get_line("G♭♭2"), Some(8.0)); // = F2
+        assert_eq!(get_line("A＃＃1"), Some(4.0)); // = B1
+        assert_eq!(get_line("E♭♭1"), None); // = D1 は範囲外
+    }
 }